@@ -0,0 +1,160 @@
+use crate::{
+    interval::Interval,
+    ray::Ray,
+    vec3::{Direction, Point3},
+};
+
+// Boxes thinner than this along some axis (e.g. those of planar shapes such as `Quad`) are padded so that the slab test stays reliable.
+const MINIMUM_THICKNESS: f64 = 1e-4;
+
+// An axis-aligned bounding box, stored as one interval per world axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+impl Aabb {
+    pub const EMPTY: Aabb = Aabb {
+        x: Interval::EMPTY,
+        y: Interval::EMPTY,
+        z: Interval::EMPTY,
+    };
+
+    // For shapes with no finite extent, such as `Plane`.
+    pub const UNIVERSE: Aabb = Aabb {
+        x: Interval::FULL,
+        y: Interval::FULL,
+        z: Interval::FULL,
+    };
+
+    pub fn new(x: Interval, y: Interval, z: Interval) -> Self {
+        Self { x, y, z }.pad_to_minimums()
+    }
+
+    // The box with `a` and `b` as opposite corners, in any order.
+    pub fn from_points(a: Point3, b: Point3) -> Self {
+        Self::new(
+            Interval::new(a.x.min(b.x), a.x.max(b.x)),
+            Interval::new(a.y.min(b.y), a.y.max(b.y)),
+            Interval::new(a.z.min(b.z), a.z.max(b.z)),
+        )
+    }
+
+    // The box centered on `center` reaching `extent` along each axis in both directions.
+    pub fn around(center: Point3, extent: Direction) -> Self {
+        Self::from_points(center - extent, center + extent)
+    }
+
+    pub fn surrounding(a: &Aabb, b: &Aabb) -> Self {
+        Self {
+            x: Interval::enclosing(&a.x, &b.x),
+            y: Interval::enclosing(&a.y, &b.y),
+            z: Interval::enclosing(&a.z, &b.z),
+        }
+    }
+
+    // The box that `a` and `b` have in common.
+    pub fn overlap(a: &Aabb, b: &Aabb) -> Self {
+        Self::new(
+            Interval::overlap(&a.x, &b.x),
+            Interval::overlap(&a.y, &b.y),
+            Interval::overlap(&a.z, &b.z),
+        )
+    }
+
+    pub fn translate(&self, offset: Direction) -> Self {
+        Self {
+            x: self.x.shift(offset.x),
+            y: self.y.shift(offset.y),
+            z: self.z.shift(offset.z),
+        }
+    }
+
+    pub fn contains(&self, point: &Point3) -> bool {
+        self.x.contains(point.x) && self.y.contains(point.y) && self.z.contains(point.z)
+    }
+
+    pub fn axis_interval(&self, axis: usize) -> &Interval {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Aabb axis out of bounds"),
+        }
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        [self.x, self.y, self.z]
+            .iter()
+            .all(|interval| interval.min.is_finite() && interval.max.is_finite())
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        Point3::new(
+            0.5 * (self.x.min + self.x.max),
+            0.5 * (self.y.min + self.y.max),
+            0.5 * (self.z.min + self.z.max),
+        )
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let sizes = [self.x.size(), self.y.size(), self.z.size()];
+        if sizes[0] > sizes[1] {
+            if sizes[0] > sizes[2] { 0 } else { 2 }
+        } else if sizes[1] > sizes[2] {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let (dx, dy, dz) = (self.x.size(), self.y.size(), self.z.size());
+        2. * (dx * dy + dy * dz + dz * dx)
+    }
+
+    pub fn hit(&self, ray: &Ray, ray_t: &Interval) -> bool {
+        self.hit_interval(ray, ray_t).is_some()
+    }
+
+    // Slab test: intersect the ray's parameter interval with the interval in which it lies between each pair of parallel faces. The result is the part of `ray_t` for which the ray is inside the box.
+    pub fn hit_interval(&self, ray: &Ray, ray_t: &Interval) -> Option<Interval> {
+        let mut t_min = ray_t.min;
+        let mut t_max = ray_t.max;
+
+        for axis in 0..3 {
+            let interval = self.axis_interval(axis);
+            let inverse_direction = 1.0 / ray.direction[axis];
+
+            let t0 = (interval.min - ray.origin[axis]) * inverse_direction;
+            let t1 = (interval.max - ray.origin[axis]) * inverse_direction;
+            let (near, far) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+
+            t_min = t_min.max(near);
+            t_max = t_max.min(far);
+
+            if t_max <= t_min {
+                return None;
+            }
+        }
+
+        Some(Interval::new(t_min, t_max))
+    }
+
+    fn pad_to_minimums(self) -> Self {
+        let pad = |interval: Interval| {
+            if interval.size() < MINIMUM_THICKNESS {
+                interval.expand(MINIMUM_THICKNESS)
+            } else {
+                interval
+            }
+        };
+        Self {
+            x: pad(self.x),
+            y: pad(self.y),
+            z: pad(self.z),
+        }
+    }
+}
@@ -13,11 +13,14 @@ use rayon::prelude::*;
 use crate::{
     color::Color,
     file,
-    hittables::Hittable,
+    hittables::{HitRecord, Hittable},
     image::Image,
     interval::Interval,
+    materials::ScatterRecord,
+    pdf::{HittablePdf, Pdf},
     progress,
     ray::Ray,
+    spectrum,
     vec3::{Direction, Point3},
     viewport::Viewport,
 };
@@ -31,6 +34,9 @@ pub struct CameraParameters {
     pub focal_distance: f64,
     pub defocus_angle_in_degrees: f64,
     pub vertical_fov_in_degrees: f64,
+    // Each ray is sent at a random time between these, so anything moving in between is blurred. Make them equal for a still.
+    pub shutter_open: f64,
+    pub shutter_close: f64,
 }
 
 #[derive(Clone)]
@@ -44,6 +50,10 @@ pub struct Camera {
     defocus_disk_u: Direction,
     defocus_disk_v: Direction,
     defocus_angle: f64,
+    shutter: Interval,
+    lights: Option<Arc<dyn Hittable>>,
+    russian_roulette: Option<usize>,
+    spectral: bool,
 }
 
 impl Camera {
@@ -57,6 +67,8 @@ impl Camera {
             focal_distance,
             defocus_angle_in_degrees,
             vertical_fov_in_degrees,
+            shutter_open,
+            shutter_close,
         } = params;
         assert!(image_width > 0, "Image width needs to be greater than zero");
         assert!(1e-8 < aspect_ratio, "Aspect ratio is too low");
@@ -65,6 +77,10 @@ impl Camera {
             "Camera up direction vector too close to zero"
         );
         assert!(1e-8 < focal_distance, "Focal distance is too small");
+        assert!(
+            shutter_open <= shutter_close,
+            "Shutter must open before it closes"
+        );
 
         let defocus_angle = defocus_angle_in_degrees.to_radians();
         let vertical_fov = vertical_fov_in_degrees.to_radians();
@@ -84,7 +100,7 @@ impl Camera {
             look_from - focal_distance * w - viewport.u / 2. - viewport.v / 2.;
         let center_of_top_left_pixel = viewport_top_left_corner + 0.5 * (pixel_du + pixel_dv);
 
-        let defocus_radius = focal_distance * (defocus_angle / 2. as f64).tan();
+        let defocus_radius = focal_distance * (defocus_angle / 2.).tan();
         let defocus_disk_u = u * defocus_radius;
         let defocus_disk_v = v * defocus_radius;
 
@@ -98,9 +114,31 @@ impl Camera {
             defocus_disk_u,
             defocus_disk_v,
             defocus_angle,
+            shutter: Interval::new(shutter_open, shutter_close),
+            lights: None,
+            russian_roulette: None,
+            spectral: false,
         }
     }
 
+    // Shapes that give off light, e.g. the `Sphere`s and `Quad`s with a `Light` material, to aim some of the rays bounced off matt surfaces at. Scenes lit by small lights are much less noisy that way. The lights should also be in the world: this only tells the camera where to look for them.
+    pub fn with_lights(mut self, lights: Arc<dyn Hittable>) -> Self {
+        self.lights = Some(lights);
+        self
+    }
+
+    // End paths at random once they've bounced `min_bounces` times, more likely the less light they can still carry back. `max_depth` is then only a safety cap, and can be set much higher without slowing things down much.
+    pub fn with_russian_roulette(mut self, min_bounces: usize) -> Self {
+        self.russian_roulette = Some(min_bounces);
+        self
+    }
+
+    // Follow light at particular wavelengths rather than as red, green and blue, turning the colors of materials and lights into spectra and the light found back into colors at the end. This is needed for glass with `Dielectric::with_dispersion` to split white light into rainbows, and is otherwise a little slower and noisier for the same picture.
+    pub fn with_spectral_rendering(mut self) -> Self {
+        self.spectral = true;
+        self
+    }
+
     // Specify `image_name` without extension, thus "example" rather than "example.ppm".
     pub fn render<T: Hittable + std::marker::Send + std::marker::Sync>(
         &self,
@@ -155,9 +193,17 @@ impl Camera {
                     .map(|j| {
                         let mut pixel_color = Color::new(0.0, 0.0, 0.0);
                         for _ in 0..samples_per_pixel {
-                            let ray = camera.get_ray(i, j, &mut rng);
+                            let hero = camera
+                                .spectral
+                                .then(|| spectrum::sample_hero_wavelength(&mut rng));
+                            let ray = camera.get_ray(i, j, &mut rng).with_wavelength(hero);
+                            let color =
+                                camera.ray_color(&ray, world, max_depth, background, &mut rng);
                             pixel_color = pixel_color
-                                + camera.ray_color(&ray, world, max_depth, background, &mut rng);
+                                + match hero {
+                                    Some(hero) => spectrum::to_rgb(&color, hero),
+                                    None => color,
+                                };
                         }
                         brightness * pixel_color / samples_per_pixel as f64
                     })
@@ -174,31 +220,157 @@ impl Camera {
         pixels
     }
 
+    // Follow a path of bounces from the camera, adding up the light found along the way. `throughput` is the fraction of each color that makes it back to the camera from the current bounce. The path ends when it leaves the scene, meets something that doesn't scatter, after `max_depth` bounces, or sooner, by Russian roulette, if that's enabled. If the ray has a hero wavelength, the three channels of every color here are instead the light at its three wavelengths.
     fn ray_color<T: Hittable>(
         &self,
         ray: &Ray,
         world: &T,
-        depth: usize,
+        max_depth: usize,
         background: fn(&Ray) -> Color,
         rng: &mut SmallRng,
     ) -> Color {
-        if depth == 0 {
-            return Color::new(0., 0., 0.);
-        }
-        if let Some(record) = world.hit(ray, &Interval::new(0.001, f64::INFINITY), rng) {
-            let color_from_attenuation = if let Some((scattered, attenuation)) = record
-                .material
-                .scatter(&ray, &record.point, &record.normal, record.front_face, rng)
-            {
-                attenuation * self.ray_color(&scattered, world, depth - 1, background, rng)
-            } else {
-                Color::new(0., 0., 0.)
+        let mut color = Color::new(0., 0., 0.);
+        let mut throughput = Color::new(1., 1., 1.);
+        let mut ray = ray.clone();
+        let hero = ray.wavelength;
+        let mut single_wavelength = false;
+        // The share of any light given off by the next surface hit that should count, the rest having been counted already by sampling the lights directly.
+        let mut emission_weight = 1.;
+        let mut media = Media::default();
+
+        for bounce in 0..max_depth {
+            let Some(record) = world.hit(&ray, &Interval::new(0.001, f64::INFINITY), rng) else {
+                let transmittance = media.transmittance(f64::INFINITY);
+                let background = spectrum::sample(&background(&ray), hero);
+                return color + throughput * transmittance * background;
             };
-            let color_from_emission = record.material.emit(&record.point);
-            color_from_attenuation + color_from_emission
-        } else {
-            background(&ray)
+            throughput = throughput * media.transmittance(record.t);
+            let emitted = spectrum::sample(&record.material.emit(&record), hero);
+            color = color + emission_weight * throughput.clone() * emitted;
+
+            // Each wavelength leaves a dispersive material in its own direction, so only the hero wavelength can be followed on. It then stands in for all three, which is still right on average, as it was chosen at random.
+            if hero.is_some() && !single_wavelength && record.material.is_dispersive() {
+                throughput = Color::new(3. * throughput.r, 0., 0.);
+                single_wavelength = true;
+            }
+
+            let material_pdf = match record.material.scatter(&ray, &record, rng) {
+                None => break,
+                Some(ScatterRecord::Specular {
+                    ray: scattered,
+                    attenuation,
+                }) => {
+                    let attenuation = if record.material.is_spectral() {
+                        attenuation
+                    } else {
+                        spectrum::sample(&attenuation, hero)
+                    };
+                    throughput = throughput * attenuation;
+                    emission_weight = 1.;
+                    media.cross(&record, &scattered.direction, hero);
+                    ray = scattered.with_wavelength(hero);
+                    if !self.survives_roulette(bounce, &mut throughput, rng) {
+                        break;
+                    }
+                    continue;
+                }
+                Some(ScatterRecord::Diffuse { pdf }) => pdf,
+            };
+
+            // Light can reach a matt surface from a light directly, or by bouncing off other things. Each is found in two ways: by aiming at the lights, which is good for small lights, and by following the material, which is good for big lights and shiny materials. To avoid counting any light twice, the two are blended by multiple importance sampling, weighting each in favor of the way that was more likely to find it.
+            let light_pdf = self
+                .lights
+                .as_ref()
+                .map(|lights| HittablePdf::new(lights.as_ref(), record.point, ray.time));
+            if light_pdf.is_some() {
+                let direct =
+                    self.sample_lights(&ray, &record, world, material_pdf.as_ref(), &media, rng);
+                color = color + throughput.clone() * direct;
+            }
+
+            let direction = material_pdf.generate(rng);
+            let pdf_value = material_pdf.value(&direction, rng);
+            if direction.near_zero() || pdf_value <= 0. {
+                break;
+            }
+            let bsdf = spectrum::sample(&record.material.eval(&ray, &record, &direction), hero);
+            if bsdf.is_black() {
+                break;
+            }
+            emission_weight = match &light_pdf {
+                Some(light_pdf) => power_heuristic(pdf_value, light_pdf.value(&direction, rng)),
+                None => 1.,
+            };
+            throughput = throughput * bsdf / pdf_value;
+            media.cross(&record, &direction, hero);
+            ray = Ray::new(record.point, direction)
+                .with_time(ray.time)
+                .with_wavelength(hero);
+            if !self.survives_roulette(bounce, &mut throughput, rng) {
+                break;
+            }
+        }
+        color
+    }
+
+    // Russian roulette: a path that can only add a little more light to the pixel is ended at random, with a probability that grows as its throughput shrinks. The paths that survive make up for the ones that don't by carrying proportionately more light, so the image is the same on average, but less time is spent on dark paths.
+    fn survives_roulette(&self, bounce: usize, throughput: &mut Color, rng: &mut SmallRng) -> bool {
+        let Some(min_bounces) = self.russian_roulette else {
+            return true;
+        };
+        if bounce + 1 < min_bounces {
+            return true;
         }
+        let survival = throughput.r.max(throughput.g).max(throughput.b).min(1.);
+        if survival <= 0. || rng.random::<f64>() >= survival {
+            return false;
+        }
+        *throughput = throughput.clone() / survival;
+        true
+    }
+
+    // Light reaching `record` directly from a randomly chosen point on one of the lights, if nothing's in the way, weighted for multiple importance sampling.
+    fn sample_lights<T: Hittable>(
+        &self,
+        ray: &Ray,
+        record: &HitRecord,
+        world: &T,
+        material_pdf: &dyn Pdf,
+        media: &Media,
+        rng: &mut SmallRng,
+    ) -> Color {
+        let black = Color::new(0., 0., 0.);
+        let Some(lights) = &self.lights else {
+            return black;
+        };
+        let light_pdf = HittablePdf::new(lights.as_ref(), record.point, ray.time);
+        let direction = light_pdf.generate(rng);
+        if direction.near_zero() {
+            return black;
+        }
+        let pdf_value = light_pdf.value(&direction, rng);
+        if pdf_value <= 0. {
+            return black;
+        }
+        let bsdf = spectrum::sample(
+            &record.material.eval(ray, record, &direction),
+            ray.wavelength,
+        );
+        if bsdf.is_black() {
+            return black;
+        }
+
+        // The shadow ray: whatever it hits first is what's seen in that direction, whether it's the light or something in front of it.
+        let shadow_ray = Ray::new(record.point, direction).with_time(ray.time);
+        let Some(hit) = world.hit(&shadow_ray, &Interval::new(0.001, f64::INFINITY), rng) else {
+            return black;
+        };
+        let emitted = spectrum::sample(&hit.material.emit(&hit), ray.wavelength);
+        if emitted.is_black() {
+            return black;
+        }
+        let weight = power_heuristic(pdf_value, material_pdf.value(&direction, rng));
+        weight * bsdf * emitted * media.transmittance(hit.t) / pdf_value
     }
 
     fn get_ray(&self, i: u32, j: u32, rng: &mut SmallRng) -> Ray {
@@ -215,10 +387,15 @@ impl Camera {
             self.defocus_disk_sample(rng)
         };
         let ray_direction = pixel_sample - ray_origin;
+        let ray_time = if self.shutter.size() > 0. {
+            rng.random_range(self.shutter.min..self.shutter.max)
+        } else {
+            self.shutter.min
+        };
         if ray_direction.near_zero() {
-            Ray::new(ray_origin, self.look_from - self.look_at)
+            Ray::new(ray_origin, self.look_from - self.look_at).with_time(ray_time)
         } else {
-            Ray::new(ray_origin, ray_direction)
+            Ray::new(ray_origin, ray_direction).with_time(ray_time)
         }
     }
 
@@ -231,3 +408,271 @@ impl Camera {
 fn sample_square(rng: &mut SmallRng) -> Direction {
     Direction::new(rng.random_range(-0.5..0.5), rng.random_range(-0.5..0.5), 0.)
 }
+
+// The absorbing materials, such as coloured glass, that a path is inside: the absorption coefficient of each, innermost last.
+#[derive(Default)]
+struct Media(Vec<Color>);
+
+impl Media {
+    // The fraction of each color left after travelling `distance` through the innermost medium, by the Beer-Lambert law.
+    fn transmittance(&self, distance: f64) -> Color {
+        let Some(absorption) = self.0.last() else {
+            return Color::new(1., 1., 1.);
+        };
+        let channel = |coefficient: f64| {
+            if coefficient > 0. {
+                (-coefficient * distance).exp()
+            } else {
+                1.
+            }
+        };
+        Color::new(
+            channel(absorption.r),
+            channel(absorption.g),
+            channel(absorption.b),
+        )
+    }
+
+    // Light scattered through a surface into an object's interior is inside it until it's scattered back out.
+    fn cross(&mut self, record: &HitRecord, direction: &Direction, hero: Option<f64>) {
+        let Some(absorption) = record.material.absorption() else {
+            return;
+        };
+        if direction.dot(&record.normal) >= 0. {
+            return;
+        }
+        if record.front_face {
+            self.0.push(spectrum::sample(&absorption, hero));
+        } else {
+            self.0.pop();
+        }
+    }
+}
+
+// The weight for a sample chosen with density `chosen`, when it might instead have been found by another method with density `other`. Squaring favors whichever was more likely still more strongly than a plain ratio would, which works better in practice (Veach, 1997).
+fn power_heuristic(chosen: f64, other: f64) -> f64 {
+    let (a, b) = (chosen * chosen, other * other);
+    if a + b > 0. { a / (a + b) } else { 0. }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::{
+        hittables::{HittableList, plane::Plane, sphere::Sphere},
+        materials::{Dielectric, Lambertian, Light, Material},
+        pdf::CosinePdf,
+    };
+
+    // A matt surface that also glows. Seen from inside a closed sphere of it, every path finds the same glow at every bounce, dimmed by the albedo each time, so the color is 1 + a + a² + ... = 1 / (1 - a).
+    struct Glowing {
+        albedo: f64,
+    }
+
+    impl Material for Glowing {
+        fn scatter(
+            &self,
+            _incident_ray: &Ray,
+            record: &HitRecord,
+            _rng: &mut SmallRng,
+        ) -> Option<ScatterRecord> {
+            Some(ScatterRecord::Diffuse {
+                pdf: Box::new(CosinePdf::new(&record.normal)),
+            })
+        }
+
+        fn eval(&self, _incident_ray: &Ray, record: &HitRecord, direction: &Direction) -> Color {
+            let cosine = record.normal.dot(&direction.normalize()).max(0.);
+            Color::new(self.albedo, self.albedo, self.albedo) * cosine / PI
+        }
+
+        fn emit(&self, _record: &HitRecord) -> Color {
+            Color::new(1., 1., 1.)
+        }
+    }
+
+    // A grey floor lit only by a small glowing sphere straight above the point the camera looks at.
+    fn floor_under_a_light() -> (HittableList, Arc<Sphere>) {
+        let light = Arc::new(Sphere::new(
+            Point3::new(0., 4., 0.),
+            0.5,
+            Arc::new(Light::new(Color::new(16., 16., 16.))),
+        ));
+        let mut world = HittableList::new();
+        world.add(Arc::new(Plane::new(
+            Point3::new(0., 0., 0.),
+            Direction::new(0., 1., 0.),
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )));
+        world.add(light.clone());
+        (world, light)
+    }
+
+    #[test]
+    fn sampling_lights_gives_the_same_light_with_less_noise() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let (world, light) = floor_under_a_light();
+        let camera = Camera::new(CameraParameters {
+            aspect_ratio: 1.,
+            image_width: 1,
+            look_from: Point3::new(1., 1., 0.),
+            look_at: Point3::new(0., 0., 0.),
+            up: Direction::new(0., 1., 0.),
+            focal_distance: 1.,
+            defocus_angle_in_degrees: 0.,
+            vertical_fov_in_degrees: 90.,
+            shutter_open: 0.,
+            shutter_close: 0.,
+        });
+        let background = |_: &Ray| Color::new(0., 0., 0.);
+        let ray = Ray::new(Point3::new(1., 1., 0.), Direction::new(-1., -1., 0.));
+
+        // A sphere of radiance L subtending an angle θ from its center straight overhead gives an irradiance of πL sin²θ, and a matt surface reflects albedo / π of it.
+        let sin_squared = 0.5f64.powi(2) / 4f64.powi(2);
+        let expected = 0.5 * 16. * sin_squared;
+
+        let mean = |camera: &Camera, samples: usize, rng: &mut SmallRng| {
+            let total: f64 = (0..samples)
+                .map(|_| camera.ray_color(&ray, &world, 10, background, rng).r)
+                .sum();
+            total / samples as f64
+        };
+
+        // Following the material alone only finds the light one time in sixteen.
+        let without_lights = mean(&camera, 40_000, &mut rng);
+        assert!((without_lights - expected).abs() < 0.05, "{without_lights}");
+
+        // Aiming at it finds it every time, so far fewer samples give the same answer.
+        let camera = camera.with_lights(light);
+        let with_lights = mean(&camera, 1_000, &mut rng);
+        assert!((with_lights - expected).abs() < 0.01, "{with_lights}");
+    }
+
+    #[test]
+    fn light_and_material_weights_add_up_to_one() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let (_, light) = floor_under_a_light();
+        let point = Point3::new(0.3, 0., -0.2);
+        let light_pdf = HittablePdf::new(light.as_ref(), point, 0.);
+        let material_pdf = CosinePdf::new(&Direction::new(0., 1., 0.));
+
+        // Each direction toward the light could have been found either way, and between them, the two weights count it exactly once. Directions that miss the light can only be found by following the material, which then gets all the weight.
+        for _ in 0..100 {
+            for direction in [
+                light_pdf.generate(&mut rng),
+                material_pdf.generate(&mut rng),
+            ] {
+                let from_light = light_pdf.value(&direction, &mut rng);
+                let from_material = material_pdf.value(&direction, &mut rng);
+                let total = power_heuristic(from_light, from_material)
+                    + power_heuristic(from_material, from_light);
+                assert!((total - 1.).abs() < 1e-12, "{total}");
+            }
+        }
+    }
+
+    #[test]
+    fn russian_roulette_keeps_the_average() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let albedo = 0.5;
+        let world = Sphere::new(Point3::new(0., 0., 0.), 10., Arc::new(Glowing { albedo }));
+        let camera = Camera::new(CameraParameters {
+            aspect_ratio: 1.,
+            image_width: 1,
+            look_from: Point3::new(0., 0., 0.),
+            look_at: Point3::new(0., 0., -1.),
+            up: Direction::new(0., 1., 0.),
+            focal_distance: 1.,
+            defocus_angle_in_degrees: 0.,
+            vertical_fov_in_degrees: 90.,
+            shutter_open: 0.,
+            shutter_close: 0.,
+        });
+        let background = |_: &Ray| Color::new(0., 0., 0.);
+        let ray = Ray::new(Point3::new(0., 0., 0.), Direction::new(0., 0., -1.));
+        let expected = 1. / (1. - albedo);
+
+        // Without roulette, every path is cut off after `max_depth` bounces, losing the rest of the series.
+        let cut_off = camera.ray_color(&ray, &world, 3, background, &mut rng);
+        assert!((cut_off.r - 1.75).abs() < 1e-9);
+
+        // With it, paths end early, but the average is still the whole series.
+        let camera = camera.with_russian_roulette(1);
+        let samples = 20_000;
+        let total: f64 = (0..samples)
+            .map(|_| camera.ray_color(&ray, &world, 1000, background, &mut rng).r)
+            .sum();
+        let mean = total / samples as f64;
+        assert!((mean - expected).abs() < 0.03, "{mean}");
+    }
+
+    #[test]
+    fn coloured_glass_absorbs_along_the_path_inside() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        // With a refractive index of 1, nothing is reflected or bent, so the ray goes straight through, 2 units inside.
+        let glass = Dielectric::new(1.).with_absorption(Color::new(1., 0., 0.5));
+        let world = Sphere::new(Point3::new(0., 0., 0.), 1., Arc::new(glass));
+        let camera = Camera::new(CameraParameters {
+            aspect_ratio: 1.,
+            image_width: 1,
+            look_from: Point3::new(0., 0., 5.),
+            look_at: Point3::new(0., 0., 0.),
+            up: Direction::new(0., 1., 0.),
+            focal_distance: 1.,
+            defocus_angle_in_degrees: 0.,
+            vertical_fov_in_degrees: 90.,
+            shutter_open: 0.,
+            shutter_close: 0.,
+        });
+        let background = |_: &Ray| Color::new(1., 1., 1.);
+        let ray = Ray::new(Point3::new(0., 0., 5.), Direction::new(0., 0., -1.));
+        let color = camera.ray_color(&ray, &world, 10, background, &mut rng);
+        assert!((color.r - (-2f64).exp()).abs() < 1e-9);
+        assert!((color.g - 1.).abs() < 1e-9);
+        assert!((color.b - (-1f64).exp()).abs() < 1e-9);
+
+        // Nearer the edge, the path inside is shorter, so less is absorbed.
+        let ray = Ray::new(Point3::new(0., 0.6, 5.), Direction::new(0., 0., -1.));
+        let color = camera.ray_color(&ray, &world, 10, background, &mut rng);
+        assert!((color.r - (-1.6f64).exp()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn spectral_rendering_keeps_grey_grey() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let albedo = 0.5;
+        let world = Sphere::new(Point3::new(0., 0., 0.), 10., Arc::new(Glowing { albedo }));
+        let camera = Camera::new(CameraParameters {
+            aspect_ratio: 1.,
+            image_width: 1,
+            look_from: Point3::new(0., 0., 0.),
+            look_at: Point3::new(0., 0., -1.),
+            up: Direction::new(0., 1., 0.),
+            focal_distance: 1.,
+            defocus_angle_in_degrees: 0.,
+            vertical_fov_in_degrees: 90.,
+            shutter_open: 0.,
+            shutter_close: 0.,
+        })
+        .with_spectral_rendering();
+        let background = |_: &Ray| Color::new(0., 0., 0.);
+
+        // A grey furnace gives the same light at every wavelength, so whichever are sampled, it comes out the same grey as in RGB.
+        let samples = 5_000;
+        let mut total = Color::new(0., 0., 0.);
+        for _ in 0..samples {
+            let hero = spectrum::sample_hero_wavelength(&mut rng);
+            let ray = Ray::new(Point3::new(0., 0., 0.), Direction::new(0., 0., -1.))
+                .with_wavelength(Some(hero));
+            let color = camera.ray_color(&ray, &world, 10, background, &mut rng);
+            total = total + spectrum::to_rgb(&color, hero);
+        }
+        let mean = total / samples as f64;
+        let expected = (1. - albedo.powi(10)) / (1. - albedo);
+        for channel in [mean.r, mean.g, mean.b] {
+            assert!((channel - expected).abs() < 0.05, "{mean:?}");
+        }
+    }
+}
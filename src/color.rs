@@ -7,11 +7,11 @@ use std::{
 
 use crate::interval::Interval;
 
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Color {
-    r: f64,
-    g: f64,
-    b: f64,
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
 }
 
 fn linear_to_gamma(linear_component: f64) -> f64 {
@@ -35,6 +35,11 @@ impl Color {
             rng.random_range(range),
         )
     }
+
+    pub fn is_black(&self) -> bool {
+        self.r <= 0. && self.g <= 0. && self.b <= 0.
+    }
+
     pub fn write<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
         let r = linear_to_gamma(self.r);
         let g = linear_to_gamma(self.g);
@@ -2,11 +2,15 @@ pub mod demo {
     pub mod balloons;
     pub mod basic;
     pub mod combo;
+    pub mod landscape;
+    pub mod metals;
+    pub mod prism;
     pub mod smoke;
     pub mod sunset;
     pub mod this_floating_world;
 }
 pub mod book {
+    pub mod bouncing_spheres;
     pub mod dull_metal;
     pub mod glass;
     pub mod lambertian;
@@ -117,6 +121,8 @@ fn set_up_camera() -> Camera {
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.,
         vertical_fov_in_degrees: 20.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
@@ -131,6 +137,8 @@ pub fn wide_angle() -> Camera {
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.,
         vertical_fov_in_degrees: 90.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
@@ -145,6 +153,8 @@ fn reduce_fov() -> Camera {
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.,
         vertical_fov_in_degrees: 20.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
@@ -159,6 +169,8 @@ fn defocus() -> Camera {
         focal_distance: 10.,
         defocus_angle_in_degrees: 3.4,
         vertical_fov_in_degrees: 20.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
@@ -4,7 +4,7 @@ use crate::{
     camera::{Camera, CameraParameters},
     color::{self, Color},
     hittables::{HittableList, cube::Cube, plane::Plane},
-    materials::Metal,
+    materials::conductor::Conductor,
     ray::Ray,
     vec3::{Basis, Direction, Point3},
 };
@@ -35,13 +35,15 @@ fn sky(ray: &Ray) -> Color {
 fn set_up_camera(image_width: u32) -> Camera {
     let params = CameraParameters {
         aspect_ratio: 4.0 / 3.0,
-        image_width: image_width,
+        image_width,
         look_from: Point3::new(0., 0., 4.),
         look_at: Point3::new(0., 0., -1.),
         up: Direction::new(0., 1., 0.),
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.,
         vertical_fov_in_degrees: 20.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
@@ -50,8 +52,8 @@ fn make_world() -> HittableList {
     let ground_color = Color::new(0.2, 0.4, 0.4);
     let cube_color = Color::new(0.8, 0.2, 0.2);
 
-    let ground_material = Arc::new(Metal::new(ground_color, 0.2));
-    let cube_material = Arc::new(Metal::new(cube_color, 0.1));
+    let ground_material = Arc::new(Conductor::new(ground_color, 0.3));
+    let cube_material = Arc::new(Conductor::new(cube_color, 0.2));
 
     let ground = Arc::new(Plane::new(
         Point3::new(0., -0.5, 0.),
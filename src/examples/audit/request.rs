@@ -4,9 +4,10 @@ use crate::{
     camera::{Camera, CameraParameters},
     color::{self, Color},
     hittables::{
-        HittableList, cube::Cube, cylinder::Cylinder, plane::Plane, quad::Quad, sphere::Sphere,
+        Hittable, HittableList, cube::Cube, cylinder::Cylinder, plane::Plane, quad::Quad,
+        sphere::Sphere,
     },
-    materials::{Lambertian, Light, Metal},
+    materials::{Lambertian, Light, conductor::Conductor},
     ray::Ray,
     vec3::{Basis, Direction, Point3},
 };
@@ -19,10 +20,10 @@ fn sky(ray: &Ray) -> Color {
 }
 
 pub fn render(max_depth: usize, samples_per_pixel: usize, image_width: u32) -> io::Result<()> {
-    let world = make();
+    let (world, lights) = make();
 
     let background = sky;
-    let camera = set_up_camera(image_width);
+    let camera = set_up_camera(image_width).with_lights(lights);
     camera.render(
         &world,
         PathBuf::from("audit").join("request"),
@@ -45,18 +46,21 @@ fn set_up_camera(image_width: u32) -> Camera {
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.,
         vertical_fov_in_degrees: 20.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
 
-fn make() -> HittableList {
-    let metal_1 = Arc::new(Metal::new(Color::new(0.8, 0.8, 0.8), 0.));
-    let metal_2 = Arc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1));
+// The world, and the lights in it, for the camera to aim at.
+fn make() -> (HittableList, Arc<dyn Hittable>) {
+    let metal_1 = Arc::new(Conductor::new(Color::new(0.8, 0.8, 0.8), 0.));
+    let metal_2 = Arc::new(Conductor::new(Color::new(0.8, 0.6, 0.2), 0.2));
     let light_material = Arc::new(Light::new(Color::new(2., 2., 1.)));
     let dark_material = Arc::new(Lambertian::new(Color::new(0.05, 0.05, 0.05)));
     let red_material = Arc::new(Lambertian::new(Color::new(0.8, 0.1, 0.1)));
     let blue_material = Arc::new(Lambertian::new(Color::new(0.1, 0.1, 0.8)));
-    let ground_material = Arc::new(Metal::new(Color::new(0.2, 0.4, 0.4), 0.2));
+    let ground_material = Arc::new(Conductor::new(Color::new(0.2, 0.4, 0.4), 0.3));
 
     let ground = Arc::new(Plane::new(
         Point3::new(0., 0., 0.),
@@ -124,7 +128,7 @@ fn make() -> HittableList {
     world.add(center);
     world.add(front);
     world.add(back);
-    world.add(light);
+    world.add(light.clone());
     world.add(cylinder_left);
     world.add(cylinder_right);
     world.add(quad_00);
@@ -132,5 +136,5 @@ fn make() -> HittableList {
     world.add(quad_10);
     world.add(quad_11);
 
-    world
+    (world, light)
 }
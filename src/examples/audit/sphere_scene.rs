@@ -5,7 +5,7 @@ use crate::{
     color::Color,
     examples,
     hittables::{HittableList, plane::Plane, sphere::Sphere},
-    materials::{Dielectric, Lambertian, Metal},
+    materials::{Dielectric, Lambertian, conductor::Conductor},
     vec3::{Direction, Point3},
 };
 
@@ -35,6 +35,8 @@ fn set_up_camera(image_width: u32) -> Camera {
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.,
         vertical_fov_in_degrees: 20.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
@@ -42,8 +44,8 @@ fn set_up_camera(image_width: u32) -> Camera {
 pub fn make_world() -> HittableList {
     let ground_material = Arc::new(Lambertian::new(Color::new(0.4, 0.6, 0.)));
     let center_material = Arc::new(Lambertian::new(Color::new(0.8, 0.1, 0.1)));
-    let left_material = Arc::new(Metal::new(Color::new(0.8, 0.8, 0.8), 0.));
-    let right_material = Arc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1));
+    let left_material = Arc::new(Conductor::new(Color::new(0.8, 0.8, 0.8), 0.));
+    let right_material = Arc::new(Conductor::new(Color::new(0.8, 0.6, 0.2), 0.2));
     let rightmost_material = Arc::new(Dielectric::new(1.5));
 
     let ground = Arc::new(Plane::new(
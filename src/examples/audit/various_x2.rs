@@ -4,7 +4,7 @@ use crate::{
     camera::{Camera, CameraParameters},
     color::{self, Color},
     hittables::{HittableList, cube::Cube, cylinder::Cylinder, plane::Plane, sphere::Sphere},
-    materials::{Dielectric, Lambertian, Metal},
+    materials::{Dielectric, Lambertian, conductor::Conductor},
     ray::Ray,
     vec3::{Basis, Direction, Point3},
 };
@@ -48,13 +48,15 @@ fn sky(ray: &Ray) -> Color {
 fn set_up_camera(image_width: u32, look_from: Point3) -> Camera {
     let params = CameraParameters {
         aspect_ratio: 4.0 / 3.0,
-        image_width: image_width,
+        image_width,
         look_from,
         look_at: Point3::new(0., 0., -1.),
         up: Direction::new(0., 1., 0.),
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.,
         vertical_fov_in_degrees: 20.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
@@ -67,8 +69,8 @@ fn make_world() -> HittableList {
     let cube_material = Arc::new(Lambertian::new(cube_color));
     let ground_material = Arc::new(Lambertian::new(ground_color));
     let rightmost_material = Arc::new(Lambertian::new(rightmost_color));
-    let left_material = Arc::new(Metal::new(Color::new(0.8, 0.8, 0.8), 0.));
-    let right_material = Arc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1));
+    let left_material = Arc::new(Conductor::new(Color::new(0.8, 0.8, 0.8), 0.));
+    let right_material = Arc::new(Conductor::new(Color::new(0.8, 0.6, 0.2), 0.2));
     let glass = Arc::new(Dielectric::new(1.5));
 
     let ground = Arc::new(Plane::from_span(
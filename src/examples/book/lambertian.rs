@@ -8,9 +8,7 @@ use crate::{
 };
 
 pub fn make() -> HittableList {
-    let lambertian = Arc::new(Lambertian {
-        albedo: Color::new(0.5, 0.5, 0.5),
-    });
+    let lambertian = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
 
     let mut world = HittableList::new();
     world.add(Arc::new(Sphere::new(
@@ -4,13 +4,13 @@ use crate::{
     camera::{Camera, CameraParameters},
     color::Color,
     examples,
-    hittables::{HittableList, sphere::Sphere},
+    hittables::{HittableList, bvh::Bvh, sphere::Sphere},
     materials::{Dielectric, Lambertian, Metal},
     vec3::{Direction, Point3},
 };
 
 pub fn render(max_depth: usize, samples_per_pixel: usize) -> io::Result<()> {
-    let world = make();
+    let world = Bvh::new(make());
 
     let params = CameraParameters {
         aspect_ratio: 16.0 / 9.0,
@@ -21,6 +21,8 @@ pub fn render(max_depth: usize, samples_per_pixel: usize) -> io::Result<()> {
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.6,
         vertical_fov_in_degrees: 20.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     let camera = Camera::new(params);
 
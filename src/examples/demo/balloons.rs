@@ -4,11 +4,14 @@ use crate::{
     camera::{Camera, CameraParameters},
     color::{self, Color},
     hittables::{
-        HittableList, cylinder::Cylinder, plane::Plane, sphere::Sphere, volumes::ConstantMedium,
+        HittableList, bvh::Bvh, cylinder::Cylinder, plane::Plane, sphere::Sphere,
+        volumes::ConstantMedium,
     },
-    materials::{Dielectric, Lambertian, Metal},
+    materials::{Dielectric, Lambertian, conductor::Conductor, thin_film::ThinFilm},
+    noise::NoiseField,
     particles,
     ray::Ray,
+    textures::NoiseTexture,
     vec3::{Direction, Point3},
 };
 
@@ -37,13 +40,15 @@ fn sky(ray: &Ray) -> Color {
 fn set_up_camera(image_width: u32) -> Camera {
     let params = CameraParameters {
         aspect_ratio: 4.0 / 3.0,
-        image_width: image_width,
+        image_width,
         look_from: Point3::new(0., 1., 24.),
         look_at: Point3::new(0., 2., -1.),
         up: Direction::new(0., 1., 0.),
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.,
         vertical_fov_in_degrees: 20.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
@@ -51,8 +56,8 @@ fn set_up_camera(image_width: u32) -> Camera {
 fn make_world() -> HittableList {
     let material_ground = Arc::new(Lambertian::new(Color::new(0.0, 0.0, 0.)));
     let material_center = Arc::new(Lambertian::new(Color::new(0.8, 0.1, 0.1)));
-    let material_left = Arc::new(Metal::new(Color::new(0.8, 0.8, 0.8), 0.));
-    let material_right = Arc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1));
+    let material_left = Arc::new(Conductor::new(Color::new(0.8, 0.8, 0.8), 0.));
+    let material_right = Arc::new(Conductor::new(Color::new(0.8, 0.6, 0.2), 0.2));
     let material_rightmost = Arc::new(Dielectric::new(1.5));
 
     let ground = Arc::new(Plane::new(
@@ -107,8 +112,14 @@ fn make_world() -> HittableList {
 
     let center = Point3::new(0., 5., -3.);
     let swarm_radius = 16.;
-    let particle_radius = 0.1;
-    let particle_material = Arc::new(Lambertian::new(Color::new(4., 0.5, 0.)));
+    let particle_radius = 0.3;
+    // Soap bubbles, their films swirling between 100 and 600 nm thick.
+    let swirls = Arc::new(NoiseTexture::new(
+        Arc::new(NoiseField::clouds(7, 3.)),
+        Color::new(100., 100., 100.),
+        Color::new(600., 600., 600.),
+    ));
+    let particle_material = Arc::new(ThinFilm::from_texture(swirls, 1.33));
     let bias = 2.0;
     let sampler = particles::power_center_sampler(bias);
     let size = 180;
@@ -120,7 +131,7 @@ fn make_world() -> HittableList {
         size,
         sampler,
     );
-    world.add(Arc::new(balloons));
+    world.add(Arc::new(Bvh::new(balloons)));
 
     world
 }
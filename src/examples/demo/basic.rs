@@ -42,6 +42,8 @@ fn set_up_camera(image_width: u32) -> Camera {
         focal_distance: 10.0,
         defocus_angle_in_degrees: 0.0,
         vertical_fov_in_degrees: 20.0,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
 
     Camera::new(params)
@@ -7,7 +7,7 @@ use crate::{
     hittables::{
         HittableList, cube::Cube, cylinder::Cylinder, disk::Disk, quad::Quad, sphere::Sphere,
     },
-    materials::{Dielectric, Lambertian, Metal},
+    materials::{Dielectric, Lambertian, conductor::Conductor},
     vec3::{Basis, Direction, Point3},
 };
 
@@ -43,22 +43,22 @@ pub fn render(max_depth: usize, samples_per_pixel: usize, image_width: u32) -> i
 fn set_up_camera(image_width: u32, look_from: Point3) -> Camera {
     let params = CameraParameters {
         aspect_ratio: 16.0 / 9.0,
-        image_width: image_width,
+        image_width,
         look_from,
         look_at: Point3::new(0., 0., -1.),
         up: Direction::new(0., 1., 0.),
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.,
         vertical_fov_in_degrees: 20.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
 
 fn make_world() -> HittableList {
-    let earth = Arc::new(Lambertian {
-        albedo: Color::new(0.5, 0.5, 0.4),
-    });
-    let metal = Arc::new(Metal::new(Color::new(1., 1., 1.), 0.1));
+    let earth = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.4)));
+    let metal = Arc::new(Conductor::new(Color::new(1., 1., 1.), 0.2));
     let glass = Arc::new(Dielectric::new(1.5));
 
     let ground = Arc::new(Sphere::new(
@@ -0,0 +1,82 @@
+use std::{io, path::PathBuf, sync::Arc};
+
+use crate::{
+    camera::{Camera, CameraParameters},
+    color::Color,
+    examples::demo::sunset,
+    hittables::{HittableList, heightfield::Heightfield, plane::Plane, sphere::Sphere},
+    materials::{Lambertian, Light, conductor::Conductor},
+    noise::Perlin,
+    vec3::{Direction, Point3},
+};
+
+pub fn render(max_depth: usize, samples_per_pixel: usize, image_width: u32) -> io::Result<()> {
+    let world = make_world();
+    let camera = set_up_camera(image_width);
+    camera.render(
+        &world,
+        PathBuf::from("demo").join("landscape"),
+        max_depth,
+        samples_per_pixel,
+        sunset::sky,
+        1.,
+    )?;
+
+    Ok(())
+}
+
+fn set_up_camera(image_width: u32) -> Camera {
+    let params = CameraParameters {
+        aspect_ratio: 16.0 / 9.0,
+        image_width,
+        look_from: Point3::new(0., 4., 30.),
+        look_at: Point3::new(0., 1.5, 0.),
+        up: Direction::new(0., 1., 0.),
+        focal_distance: 10.,
+        defocus_angle_in_degrees: 0.,
+        vertical_fov_in_degrees: 35.,
+        shutter_open: 0.,
+        shutter_close: 0.,
+    };
+    Camera::new(params)
+}
+
+fn make_world() -> HittableList {
+    let material_ground = Arc::new(Lambertian::new(Color::new(0.35, 0.3, 0.2)));
+    let material_lake = Arc::new(Conductor::new(Color::new(0.5, 0.55, 0.6), 0.1));
+    let material_sun = Arc::new(Light::new(Color::new(8., 3., 0.5)));
+
+    // Rolling hills, rising into mountains towards the back.
+    let perlin = Perlin::new(7);
+    let height = |x: f64, z: f64| {
+        let rise = (-z / 40.).clamp(0., 1.);
+        let hills = perlin.fbm(&Point3::new(x / 12., 0., z / 12.), 6);
+        1.5 + (3. + 9. * rise) * hills + 6. * rise * rise
+    };
+    let terrain = Arc::new(Heightfield::from_fn(
+        Point3::new(-60., -2., -60.),
+        120.,
+        90.,
+        480,
+        360,
+        height,
+        material_ground,
+    ));
+    let lake = Arc::new(Plane::new(
+        Point3::new(0., -0.3, 0.),
+        Direction::new(0., 1., 0.),
+        material_lake,
+    ));
+    let sun = Arc::new(Sphere::new(
+        Point3::new(-40., 12., -200.),
+        10.,
+        material_sun,
+    ));
+
+    let mut world = HittableList::new();
+    world.add(terrain);
+    world.add(lake);
+    world.add(sun);
+
+    world
+}
@@ -0,0 +1,93 @@
+use std::{io, path::PathBuf, sync::Arc};
+
+use crate::{
+    camera::{Camera, CameraParameters},
+    color::{self, Color},
+    hittables::{Hittable, HittableList, plane::Plane, quad::Quad, sphere::Sphere},
+    materials::{Lambertian, Light, conductor::Conductor},
+    ray::Ray,
+    textures::Checker,
+    vec3::{Direction, Point3},
+};
+
+pub fn render(max_depth: usize, samples_per_pixel: usize, image_width: u32) -> io::Result<()> {
+    let (world, lights) = make_world();
+    let camera = set_up_camera(image_width)
+        .with_lights(lights)
+        .with_russian_roulette(3);
+    camera.render(
+        &world,
+        PathBuf::from("demo").join("metals"),
+        max_depth,
+        samples_per_pixel,
+        sky,
+        1.,
+    )?;
+
+    Ok(())
+}
+
+fn sky(ray: &Ray) -> Color {
+    let t = 0.5 * (ray.direction.y + 1.0);
+    color::lerp(Color::new(0.3, 0.3, 0.3), Color::new(0.1, 0.15, 0.25), t)
+}
+
+fn set_up_camera(image_width: u32) -> Camera {
+    let params = CameraParameters {
+        aspect_ratio: 16.0 / 9.0,
+        image_width,
+        look_from: Point3::new(0., 2.5, 9.),
+        look_at: Point3::new(0., 0.6, 0.),
+        up: Direction::new(0., 1., 0.),
+        focal_distance: 10.,
+        defocus_angle_in_degrees: 0.,
+        vertical_fov_in_degrees: 30.,
+        shutter_open: 0.,
+        shutter_close: 0.,
+    };
+    Camera::new(params)
+}
+
+// Gold, copper, aluminium and silver, left to right, each polished in front and rougher behind.
+fn make_world() -> (HittableList, Arc<dyn Hittable>) {
+    let floor = Arc::new(Lambertian::from_texture(Arc::new(Checker::from_colors(
+        0.5,
+        Color::new(0.2, 0.2, 0.2),
+        Color::new(0.7, 0.7, 0.7),
+    ))));
+    let lamp = Arc::new(Light::new(Color::new(12., 12., 12.)));
+
+    let mut world = HittableList::new();
+    world.add(Arc::new(Plane::new(
+        Point3::new(0., 0., 0.),
+        Direction::new(0., 1., 0.),
+        floor,
+    )));
+
+    let metals: [fn(f64) -> Conductor; 4] = [
+        Conductor::gold,
+        Conductor::copper,
+        Conductor::aluminium,
+        Conductor::silver,
+    ];
+    for (i, metal) in metals.iter().enumerate() {
+        let x = -3. + 2. * i as f64;
+        for (z, roughness) in [(0., 0.05), (-2.5, 0.4)] {
+            world.add(Arc::new(Sphere::new(
+                Point3::new(x, 0.8, z),
+                0.8,
+                Arc::new(metal(roughness)),
+            )));
+        }
+    }
+
+    let light: Arc<dyn Hittable> = Arc::new(Quad::new(
+        Point3::new(-2., 6., -2.),
+        Direction::new(4., 0., 0.),
+        Direction::new(0., 0., 2.),
+        lamp,
+    ));
+    world.add(light.clone());
+
+    (world, light)
+}
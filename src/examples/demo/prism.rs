@@ -0,0 +1,114 @@
+use std::{io, path::PathBuf, sync::Arc};
+
+use crate::{
+    camera::{Camera, CameraParameters},
+    color::Color,
+    hittables::{HittableList, plane::Plane, quad::Quad, sphere::Sphere, triangle::Triangle},
+    materials::{Dielectric, Lambertian, Material},
+    ray::Ray,
+    spectrum::Dispersion,
+    textures::Checker,
+    vec3::{Direction, Point3},
+};
+
+pub fn render(max_depth: usize, samples_per_pixel: usize, image_width: u32) -> io::Result<()> {
+    let world = make_world();
+    let camera = set_up_camera(image_width)
+        .with_russian_roulette(3)
+        .with_spectral_rendering();
+    camera.render(
+        &world,
+        PathBuf::from("demo").join("prism"),
+        max_depth,
+        samples_per_pixel,
+        sky,
+        1.,
+    )?;
+
+    Ok(())
+}
+
+// A dark sky crossed by bright white bands, whose edges come apart into rainbows when seen through the glass.
+fn sky(ray: &Ray) -> Color {
+    let y = ray.direction.y;
+    if y > 0. && (y * 10.).fract() < 0.25 {
+        Color::new(2., 2., 2.)
+    } else {
+        Color::new(0.02, 0.02, 0.04)
+    }
+}
+
+fn set_up_camera(image_width: u32) -> Camera {
+    let params = CameraParameters {
+        aspect_ratio: 16.0 / 9.0,
+        image_width,
+        look_from: Point3::new(0., 1.6, 8.),
+        look_at: Point3::new(0., 1.2, 0.),
+        up: Direction::new(0., 1., 0.),
+        focal_distance: 8.,
+        defocus_angle_in_degrees: 0.,
+        vertical_fov_in_degrees: 30.,
+        shutter_open: 0.,
+        shutter_close: 0.,
+    };
+    Camera::new(params)
+}
+
+// A dense flint prism, lying on its side, between two diamonds.
+fn make_world() -> HittableList {
+    let floor = Arc::new(Lambertian::from_texture(Arc::new(Checker::from_colors(
+        0.5,
+        Color::new(0.1, 0.1, 0.1),
+        Color::new(0.5, 0.5, 0.5),
+    ))));
+    let flint = Arc::new(Dielectric::new(1.5).with_dispersion(Dispersion::dense_flint()));
+    let diamond = Arc::new(Dielectric::new(2.4).with_dispersion(Dispersion::diamond()));
+
+    let mut world = HittableList::new();
+    world.add(Arc::new(Plane::new(
+        Point3::new(0., 0., 0.),
+        Direction::new(0., 1., 0.),
+        floor,
+    )));
+    add_prism(&mut world, 1.8, 1.6, flint);
+    for x in [-2.6, 2.6] {
+        world.add(Arc::new(Sphere::new(
+            Point3::new(x, 0.7, 0.5),
+            0.7,
+            diamond.clone(),
+        )));
+    }
+
+    world
+}
+
+// A prism along the x axis, `length` long, whose ends are equilateral triangles with sides of `side`, the apex at the top.
+fn add_prism(world: &mut HittableList, length: f64, side: f64, material: Arc<dyn Material>) {
+    let height = side * 3f64.sqrt() / 2.;
+    let base = 0.5;
+    let corners = [
+        Point3::new(-length / 2., base + height, 0.),
+        Point3::new(-length / 2., base, side / 2.),
+        Point3::new(-length / 2., base, -side / 2.),
+    ];
+    let along = Direction::new(length, 0., 0.);
+
+    // Each face is ordered so that its normal points outwards.
+    for i in 0..3 {
+        let (from, to) = (corners[i], corners[(i + 1) % 3]);
+        world.add(Arc::new(Quad::new(
+            from,
+            to - from,
+            along,
+            material.clone(),
+        )));
+    }
+    let [a, b, c] = corners;
+    world.add(Arc::new(Triangle::new(a, c, b, material.clone())));
+    world.add(Arc::new(Triangle::new(
+        a + along,
+        b + along,
+        c + along,
+        material,
+    )));
+}
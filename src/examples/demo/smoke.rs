@@ -45,6 +45,8 @@ fn set_up_camera(image_width: u32) -> Camera {
         focal_distance: 10.0,
         defocus_angle_in_degrees: 0.0,
         vertical_fov_in_degrees: 20.0,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
 
     Camera::new(params)
@@ -79,8 +81,7 @@ fn ground() -> Arc<Plane> {
         Direction::new(0.0, 1.0, 0.0),
         material,
     );
-    let ground = Arc::new(plane);
-    ground
+    Arc::new(plane)
 }
 
 fn sphere() -> Arc<Sphere> {
@@ -88,8 +89,7 @@ fn sphere() -> Arc<Sphere> {
     let material = Arc::new(Lambertian::new(color));
     let center = Point3::new(0.0, 0.0, -2.5);
     let radius = 0.5;
-    let sphere = Arc::new(Sphere::new(center, radius, material));
-    sphere
+    Arc::new(Sphere::new(center, radius, material))
 }
 
 fn cube() -> Arc<Cube> {
@@ -98,25 +98,22 @@ fn cube() -> Arc<Cube> {
     let center = Point3::new(-0.5, 0.5, -4.0);
     let size = 0.3;
     let orientation = &Basis::new_orthonormal();
-    let cube = Arc::new(Cube::new_oriented(center, size, orientation, material));
-    cube
+    Arc::new(Cube::new_oriented(center, size, orientation, material))
 }
 
 fn smoke(shell: Arc<dyn Hittable + 'static>, color: Color, density: f64) -> Arc<ConstantMedium> {
-    let smoke = Arc::new(ConstantMedium::new(shell, color, density));
-    smoke
+    Arc::new(ConstantMedium::new(shell, color, density))
 }
 
 fn cylinder() -> Arc<Cylinder> {
     let color = Color::new(6., 0.8, 0.8);
     let material = Arc::new(Lambertian::new(color));
-    let cylinder = Arc::new(Cylinder::new(
+    Arc::new(Cylinder::new(
         Point3::new(0.4, 0.0, -1.),
         Direction::new(0.4, 0.5, -0.4),
         0.2,
         material.clone(),
         material.clone(),
         material,
-    ));
-    cylinder
+    ))
 }
@@ -4,17 +4,20 @@ use crate::{
     camera::{Camera, CameraParameters},
     color::{self, Color},
     hittables::{
-        HittableList, cylinder::Cylinder, plane::Plane, sphere::Sphere, volumes::ConstantMedium,
+        Hittable, HittableList, cylinder::Cylinder, plane::Plane, sphere::Sphere,
+        volumes::ConstantMedium,
     },
-    materials::{Dielectric, Lambertian, Light, Metal},
+    materials::{Dielectric, Lambertian, Light, conductor::Conductor},
     ray::Ray,
     vec3::{Direction, Point3},
 };
 
 pub fn render(max_depth: usize, samples_per_pixel: usize, image_width: u32) -> io::Result<()> {
-    let world = make_world();
+    let (world, lights) = make_world();
     let background = sky;
-    let camera = set_up_camera(image_width);
+    let camera = set_up_camera(image_width)
+        .with_lights(lights)
+        .with_russian_roulette(3);
     camera.render(
         &world,
         PathBuf::from("demo").join("sunset"),
@@ -30,29 +33,32 @@ pub fn render(max_depth: usize, samples_per_pixel: usize, image_width: u32) -> i
 fn set_up_camera(image_width: u32) -> Camera {
     let params = CameraParameters {
         aspect_ratio: 4.0 / 3.0,
-        image_width: image_width,
+        image_width,
         look_from: Point3::new(0., 1., 24.),
         look_at: Point3::new(0., 2., -1.),
         up: Direction::new(0., 1., 0.),
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.,
         vertical_fov_in_degrees: 20.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
 
-fn sky(ray: &Ray) -> Color {
+pub fn sky(ray: &Ray) -> Color {
     let t = 0.5 * (ray.direction.y + 1.0);
     let horizon = Color::new(0.7, 0.5, 0.0);
     let zenith = Color::new(0.05, 0.05, 0.3);
     color::lerp(horizon, zenith, t)
 }
 
-fn make_world() -> HittableList {
+// The world, and the lights in it, for the camera to aim at.
+fn make_world() -> (HittableList, Arc<dyn Hittable>) {
     let material_ground = Arc::new(Lambertian::new(Color::new(0.0, 0.0, 0.)));
     let material_center = Arc::new(Lambertian::new(Color::new(0.8, 0.1, 0.1)));
-    let material_left = Arc::new(Metal::new(Color::new(0.8, 0.8, 0.8), 0.));
-    let material_right = Arc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1));
+    let material_left = Arc::new(Conductor::new(Color::new(0.8, 0.8, 0.8), 0.));
+    let material_right = Arc::new(Conductor::new(Color::new(0.8, 0.6, 0.2), 0.2));
     let material_rightmost = Arc::new(Dielectric::new(1.5));
     let material_light = Arc::new(Light::new(Color::new(4., 0.5, 0.)));
 
@@ -104,9 +110,9 @@ fn make_world() -> HittableList {
     world.add(left);
     world.add(right);
     world.add(rightmost);
-    world.add(light);
+    world.add(light.clone());
     world.add(outer_haze);
     world.add(inner_haze);
 
-    world
+    (world, light)
 }
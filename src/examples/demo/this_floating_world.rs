@@ -4,7 +4,7 @@ use crate::{
     camera::{Camera, CameraParameters},
     color::{self, Color},
     hittables::{HittableList, cube::Cube, plane::Plane, sphere::Sphere},
-    materials::{Dielectric, Light, Metal},
+    materials::{Dielectric, Light, conductor::Conductor},
     ray::Ray,
     vec3::{Basis, Direction, Point3},
 };
@@ -43,15 +43,17 @@ fn set_up_camera(image_width: u32) -> Camera {
         focal_distance: 10.,
         defocus_angle_in_degrees: 0.,
         vertical_fov_in_degrees: 30.,
+        shutter_open: 0.,
+        shutter_close: 0.,
     };
     Camera::new(params)
 }
 
 fn make() -> HittableList {
     let water = Arc::new(Dielectric::new(1.33));
-    let metal_1 = Arc::new(Metal::new(Color::new(0.1, 0.2, 0.5), 0.5));
+    let metal_1 = Arc::new(Conductor::new(Color::new(0.1, 0.2, 0.5), 0.5));
     let glass = Arc::new(Dielectric::new(1.5));
-    let metal_2 = Arc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1));
+    let metal_2 = Arc::new(Conductor::new(Color::new(0.8, 0.6, 0.2), 0.2));
     let light_material = Arc::new(Light::new(Color::new(2., 2., 1.)));
 
     let ground = Arc::new(Plane::new(
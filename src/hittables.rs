@@ -1,17 +1,36 @@
+pub mod box3;
+pub mod bvh;
+pub mod capsule;
+pub mod cone;
+pub mod csg;
 pub mod cube;
 pub mod cylinder;
 pub mod disk;
+pub mod frustum;
+pub mod heightfield;
+pub mod mesh;
 pub mod plane;
 pub mod quad;
+pub mod quadric;
+pub mod sdf;
 pub mod sphere;
+pub mod torus;
+pub mod transform;
+pub mod triangle;
 pub mod tube;
 pub mod volumes;
 
-use std::sync::Arc;
+use std::{f64::consts::PI, sync::Arc};
 
-use rand::rngs::SmallRng;
+use rand::{Rng, rngs::SmallRng};
 
-use crate::{interval::Interval, materials::Material, ray::Ray, vec3::Direction};
+use crate::{
+    aabb::Aabb,
+    interval::Interval,
+    materials::Material,
+    ray::Ray,
+    vec3::{self, Direction, Point3},
+};
 
 pub struct HitRecord {
     pub point: crate::vec3::Point3,
@@ -19,6 +38,10 @@ pub struct HitRecord {
     pub t: f64,
     pub material: Arc<dyn Material>,
     pub front_face: bool,
+    pub u: f64, // Surface coordinates, for looking up textures.
+    pub v: f64,
+    pub tangent: Direction, // Unit vector in the surface, in the direction of increasing u.
+    pub bitangent: Direction, // Completes a right-handed basis with the tangent and outward normal.
 }
 
 impl HitRecord {
@@ -30,6 +53,7 @@ impl HitRecord {
         ray: &Ray,
     ) -> Self {
         let front_face = ray.direction.dot(&outward_normal) < 0.;
+        let [tangent, bitangent] = vec3::orthonormal_basis_2d(&outward_normal);
         Self {
             point,
             normal: if front_face {
@@ -40,18 +64,137 @@ impl HitRecord {
             t,
             material,
             front_face,
+            u: 0.,
+            v: 0.,
+            tangent,
+            bitangent,
+        }
+    }
+
+    pub fn with_uv(mut self, u: f64, v: f64) -> Self {
+        self.u = u;
+        self.v = v;
+        self
+    }
+
+    // Orient the tangent frame so that the tangent points along `tangent`, or as near as possible while staying perpendicular to the normal. Where that's impossible (e.g. at the poles of a sphere), the arbitrary default frame is kept.
+    pub fn with_tangent(mut self, tangent: Direction) -> Self {
+        let outward_normal = self.outward_normal();
+        let tangent = tangent - outward_normal * outward_normal.dot(&tangent);
+        if tangent.length_squared() > 1e-16 {
+            self.tangent = tangent.normalize();
+            self.bitangent = outward_normal.cross(&self.tangent);
+        }
+        self
+    }
+
+    pub fn outward_normal(&self) -> Direction {
+        if self.front_face {
+            self.normal
+        } else {
+            -self.normal
         }
     }
 }
 
 pub trait Hittable: Send + Sync {
     fn hit(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Option<HitRecord>;
+
+    // A box enclosing the whole shape, used to skip it cheaply when a ray misses the box. Unbounded shapes return `Aabb::UNIVERSE`.
+    fn bounding_box(&self) -> Aabb;
+
+    // Every hit within `ray_t`, nearest first, as needed for constructive solid geometry. The default finds them by calling `hit` again and again, each time starting just beyond the last hit; shapes that can find them all at once should override it.
+    fn all_hits(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Vec<HitRecord> {
+        let mut hits: Vec<HitRecord> = Vec::new();
+        let mut t_min = ray_t.min;
+        while let Some(hit) = self.hit(ray, &Interval::new(t_min, ray_t.max), rng) {
+            // The gap grows with the distance, or far enough away, adding it would leave t unchanged and find the same hit forever.
+            let next = hit.t + NEXT_HIT_GAP * hit.t.abs().max(1.);
+            hits.push(hit);
+            if next <= t_min {
+                break;
+            }
+            t_min = next;
+        }
+        hits
+    }
+
+    // For shapes that can be lights: the area of the surface, or 0 if the shape can't be sampled by area. A shape that returns more than 0 must also implement `sample_surface`.
+    fn area(&self) -> f64 {
+        0.
+    }
+
+    // A point on the surface at time `time`, with every part of the surface equally likely to be chosen.
+    fn sample_surface(&self, _time: f64, _rng: &mut SmallRng) -> Option<Point3> {
+        None
+    }
+
+    // The probability density, per unit solid angle as seen from `origin`, of `random_direction` choosing `direction` at time `time`. For a shape sampled by area, a small patch of area A at distance d, tilted by angle θ to the direction, covers a solid angle of A cos θ / d².
+    fn pdf_value(
+        &self,
+        origin: &Point3,
+        direction: &Direction,
+        time: f64,
+        rng: &mut SmallRng,
+    ) -> f64 {
+        let area = self.area();
+        if area <= 0. {
+            return 1. / (4. * PI);
+        }
+        let ray = Ray::new(*origin, *direction).with_time(time);
+        let Some(hit) = self.hit(&ray, &Interval::new(0.001, f64::INFINITY), rng) else {
+            return 0.;
+        };
+        let cosine = ray.direction.dot(&hit.normal).abs();
+        hit.t * hit.t / (cosine * area)
+    }
+
+    // A direction from `origin` towards a random point on the shape. Shapes that can't be sampled return any direction at all, with all directions equally likely, which is correct but no help.
+    fn random_direction(&self, origin: &Point3, time: f64, rng: &mut SmallRng) -> Direction {
+        match self.sample_surface(time, rng) {
+            Some(point) => point - *origin,
+            None => Direction::random_unit(rng),
+        }
+    }
 }
 
+// How far beyond one hit `all_hits` starts looking for the next, so that it doesn't find the same one again, relative to the hit's distance if that's more than 1.
+const NEXT_HIT_GAP: f64 = 1e-6;
+
 impl<T: Hittable + ?Sized> Hittable for Arc<T> {
     fn hit(&self, r: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Option<HitRecord> {
         (**self).hit(r, ray_t, rng)
     }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn all_hits(&self, r: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Vec<HitRecord> {
+        (**self).all_hits(r, ray_t, rng)
+    }
+
+    fn area(&self) -> f64 {
+        (**self).area()
+    }
+
+    fn sample_surface(&self, time: f64, rng: &mut SmallRng) -> Option<Point3> {
+        (**self).sample_surface(time, rng)
+    }
+
+    fn pdf_value(
+        &self,
+        origin: &Point3,
+        direction: &Direction,
+        time: f64,
+        rng: &mut SmallRng,
+    ) -> f64 {
+        (**self).pdf_value(origin, direction, time, rng)
+    }
+
+    fn random_direction(&self, origin: &Point3, time: f64, rng: &mut SmallRng) -> Direction {
+        (**self).random_direction(origin, time, rng)
+    }
 }
 
 pub struct HittableList {
@@ -68,27 +211,58 @@ impl HittableList {
     }
 }
 
+impl Default for HittableList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Hittable for HittableList {
     fn hit(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Option<HitRecord> {
-        let mut all_hits = Vec::new();
+        let mut closest_hit: Option<HitRecord> = None;
+        let mut closest_so_far = ray_t.max;
 
-        // Collect ALL hits from all objects. This is necessary to allow a HittableList to be the boundary of a `ConstantMedium`, which creates its own interval to check entry and exit points in order to calculate the probability of hits within the volume.
+        // Each object only needs to report its nearest hit before the nearest one found so far. This still allows a `HittableList` to be the boundary of a `ConstantMedium`, which creates its own intervals to find entry and exit points.
         for object in &self.objects {
-            if let Some(hit) = object.hit(ray, &Interval::FULL, rng) {
-                all_hits.push(hit);
+            if let Some(hit) = object.hit(ray, &Interval::new(ray_t.min, closest_so_far), rng) {
+                closest_so_far = hit.t;
+                closest_hit = Some(hit);
             }
         }
 
-        // Sort by t value.
-        all_hits.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+        closest_hit
+    }
 
-        // Return first hit within the requested interval.
-        for hit in all_hits {
-            if ray_t.contains(hit.t) {
-                return Some(hit);
-            }
+    fn bounding_box(&self) -> Aabb {
+        self.objects.iter().fold(Aabb::EMPTY, |bbox, object| {
+            Aabb::surrounding(&bbox, &object.bounding_box())
+        })
+    }
+
+    // Sampled as a list of lights: each object is equally likely to be chosen.
+    fn pdf_value(
+        &self,
+        origin: &Point3,
+        direction: &Direction,
+        time: f64,
+        rng: &mut SmallRng,
+    ) -> f64 {
+        if self.objects.is_empty() {
+            return 1. / (4. * PI);
         }
+        let sum: f64 = self
+            .objects
+            .iter()
+            .map(|object| object.pdf_value(origin, direction, time, rng))
+            .sum();
+        sum / self.objects.len() as f64
+    }
 
-        None
+    fn random_direction(&self, origin: &Point3, time: f64, rng: &mut SmallRng) -> Direction {
+        if self.objects.is_empty() {
+            return Direction::random_unit(rng);
+        }
+        let index = rng.random_range(0..self.objects.len());
+        self.objects[index].random_direction(origin, time, rng)
     }
 }
@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable},
+    interval::Interval,
+    materials::Material,
+    ray::Ray,
+    vec3::{Basis, Direction, Point3},
+};
+
+// The faces of a box, named for the directions of their outward normals relative to the box's own axes. Seen from a camera looking down the -z axis at a box aligned with the world, they're where you'd expect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Face {
+    Right,  // +x
+    Left,   // -x
+    Top,    // +y
+    Bottom, // -y
+    Front,  // +z
+    Back,   // -z
+}
+
+impl Face {
+    fn from_axis(axis: usize, direction_sign: f64) -> Self {
+        match (axis, direction_sign > 0.) {
+            (0, true) => Face::Right,
+            (0, false) => Face::Left,
+            (1, true) => Face::Top,
+            (1, false) => Face::Bottom,
+            (2, true) => Face::Front,
+            _ => Face::Back,
+        }
+    }
+}
+
+// A rectangular box, with its own half-length along each of its axes, and a material for each face.
+pub struct Box3 {
+    center: Point3,
+    half_extents: [f64; 3], // Distance from the center to each pair of opposite faces.
+    materials: [Arc<dyn Material>; 6], // In the order of `Face`.
+    u: Direction,           // Local coordinate system: three orthonormal vectors.
+    v: Direction,
+    w: Direction,
+}
+
+impl Box3 {
+    // Create a box aligned with world coordinates.
+    pub fn new(center: Point3, half_extents: Direction, material: Arc<dyn Material>) -> Box3 {
+        Self::new_oriented(
+            center,
+            half_extents,
+            &Basis::new(
+                Direction::new(1.0, 0.0, 0.0),
+                Direction::new(0.0, 1.0, 0.0),
+                Direction::new(0.0, 0.0, 1.0),
+            ),
+            material,
+        )
+    }
+
+    // Create a box with custom orientation. The half-extents are measured along the axes of `orientation`.
+    pub fn new_oriented(
+        center: Point3,
+        half_extents: Direction,
+        orientation: &Basis,
+        material: Arc<dyn Material>,
+    ) -> Box3 {
+        assert!(
+            half_extents.into_iter().all(|extent| 1e-8 < extent),
+            "Size is too small"
+        );
+        Box3 {
+            center,
+            half_extents: [half_extents.x, half_extents.y, half_extents.z],
+            materials: std::array::from_fn(|_| material.clone()),
+            u: orientation.x,
+            v: orientation.y,
+            w: orientation.z,
+        }
+    }
+
+    pub fn with_face_material(mut self, face: Face, material: Arc<dyn Material>) -> Self {
+        self.materials[face as usize] = material;
+        self
+    }
+
+    // Give each face its own material, in the order of `Face`: right, left, top, bottom, front, back.
+    pub fn with_face_materials(mut self, materials: [Arc<dyn Material>; 6]) -> Self {
+        self.materials = materials;
+        self
+    }
+
+    fn world_to_local(&self, point: &Point3) -> [f64; 3] {
+        let offset = *point - self.center;
+        [
+            self.u.dot(&offset),
+            self.v.dot(&offset),
+            self.w.dot(&offset),
+        ]
+    }
+
+    fn direction_to_local(&self, direction: &Direction) -> [f64; 3] {
+        [
+            self.u.dot(direction),
+            self.v.dot(direction),
+            self.w.dot(direction),
+        ]
+    }
+
+    /* This function contains a slick way of writing the change of basis more plainly expresssed as follows.
+    Direction::new(
+            local_direction[0] * self.u.x + local_direction[1] * self.v.x + local_direction[2] * self.w.x,
+            local_direction[0] * self.u.y + local_direction[1] * self.v.y + local_direction[2] * self.w.y,
+            local_direction[0] * self.u.z + local_direction[1] * self.v.z + local_direction[2] * self.w.z,
+        )
+     */
+    fn direction_to_world(&self, local_direction: &Direction) -> Direction {
+        [self.u, self.v, self.w]
+            .into_iter()
+            .zip(local_direction)
+            .map(|(basis, s)| basis * s)
+            .reduce(|a, b| a + b)
+            .unwrap()
+    }
+}
+
+impl Hittable for Box3 {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Option<HitRecord> {
+        let local_origin = self.world_to_local(&ray.origin);
+        let local_direction = self.direction_to_local(&ray.direction);
+
+        // Start with infinite bounds: find ALL intersections first.
+        let mut t_min = f64::NEG_INFINITY;
+        let mut t_max = f64::INFINITY;
+        let mut min_axis = None;
+        let mut max_axis = None;
+        let mut min_direction_sign = 0.0;
+        let mut max_direction_sign = 0.0;
+
+        for (axis, (&origin, &direction)) in local_origin.iter().zip(&local_direction).enumerate() {
+            let size = self.half_extents[axis];
+            if direction.abs() < 1e-8 {
+                if origin.abs() > size {
+                    return None; // Parallel and outside the slab.
+                }
+                continue;
+            }
+
+            let inverse_direction = 1.0 / direction;
+            let t1 = (-size - origin) * inverse_direction;
+            let t2 = (size - origin) * inverse_direction;
+            let (slab_min, slab_max) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+
+            // The ray enters the slab through the face whose outward normal opposes it, and leaves through the other.
+            if slab_min > t_min {
+                t_min = slab_min;
+                min_axis = Some(axis);
+                min_direction_sign = -direction.signum();
+            }
+            if slab_max < t_max {
+                t_max = slab_max;
+                max_axis = Some(axis);
+                max_direction_sign = direction.signum();
+            }
+
+            if t_min > t_max {
+                return None; // Slabs do not overlap.
+            }
+        }
+
+        // Now find which hit is within the requested ray_t interval.
+        let (t, axis, direction_sign) = if ray_t.contains(t_min) {
+            (t_min, min_axis, min_direction_sign)
+        } else if ray_t.contains(t_max) {
+            (t_max, max_axis, max_direction_sign)
+        } else {
+            return None; // No intersection within the requested interval.
+        };
+
+        let point = ray.at(t);
+        let axis = axis?; // Only missing if the ray direction is zero.
+        let mut normal_local = Direction::new(0., 0., 0.);
+        normal_local[axis] = direction_sign;
+
+        // Each face is mapped onto the unit square along the two other local axes, in cyclic order, with the first of them mirrored on negative faces so that every face is seen the right way round from outside.
+        let local_point = self.world_to_local(&point);
+        let (first, second) = ((axis + 1) % 3, (axis + 2) % 3);
+        let u = (direction_sign * local_point[first] / self.half_extents[first] + 1.) / 2.;
+        let v = (local_point[second] / self.half_extents[second] + 1.) / 2.;
+        let basis = [self.u, self.v, self.w];
+
+        let world_normal = self.direction_to_world(&normal_local);
+        let material = self.materials[Face::from_axis(axis, direction_sign) as usize].clone();
+        Some(
+            HitRecord::new(point, world_normal, t, material, ray)
+                .with_uv(u, v)
+                .with_tangent(direction_sign * basis[first]),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let [a, b, c] = self.half_extents;
+        let extent =
+            |axis: usize| a * self.u[axis].abs() + b * self.v[axis].abs() + c * self.w[axis].abs();
+        Aabb::around(self.center, Direction::new(extent(0), extent(1), extent(2)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{color::Color, materials::Lambertian, vec3};
+
+    #[test]
+    fn each_face_has_its_own_material() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let walls: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let red: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.8, 0.1, 0.1)));
+        let green: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.1, 0.8, 0.1)));
+        // A room, 4 wide, 2 high and 6 deep, with a red left wall and green right wall, seen from inside.
+        let room = Box3::new(
+            Point3::new(0., 0., 0.),
+            Direction::new(2., 1., 3.),
+            walls.clone(),
+        )
+        .with_face_material(Face::Left, red.clone())
+        .with_face_material(Face::Right, green.clone());
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        for (direction, distance, expected) in [
+            (Direction::new(-1., 0., 0.), 2., &red),
+            (Direction::new(1., 0., 0.), 2., &green),
+            (Direction::new(0., 0., -1.), 3., &walls),
+        ] {
+            let ray = Ray::new(Point3::new(0., 0., 0.), direction);
+            let hit = room.hit(&ray, &ray_t, &mut rng).unwrap();
+            assert!((hit.t - distance).abs() < 1e-9);
+            assert!(!hit.front_face);
+            assert!(Arc::ptr_eq(&hit.material, expected));
+        }
+    }
+
+    #[test]
+    fn oriented_box_extents() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        // Long along the diagonal between +x and +y.
+        let s = 0.5f64.sqrt();
+        let orientation = Basis::new(
+            Direction::new(s, s, 0.),
+            Direction::new(-s, s, 0.),
+            Direction::new(0., 0., 1.),
+        );
+        let plank = Box3::new_oriented(
+            Point3::new(0., 0., 0.),
+            Direction::new(3., 0.5, 0.5),
+            &orientation,
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        );
+
+        let ray = Ray::new(Point3::new(10., 10., 0.), Direction::new(-1., -1., 0.));
+        let hit = plank
+            .hit(&ray, &Interval::new(0.001, f64::INFINITY), &mut rng)
+            .unwrap();
+        assert!(vec3::approx_eq(
+            hit.point,
+            Point3::new(3. * s, 3. * s, 0.),
+            1e-9
+        ));
+        assert!(vec3::approx_eq(hit.normal, orientation.x, 1e-9));
+
+        let bbox = plank.bounding_box();
+        assert!((bbox.x.max - 3.5 * s).abs() < 1e-9 && (bbox.z.max - 0.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,314 @@
+use std::sync::Arc;
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable, HittableList},
+    interval::Interval,
+    ray::Ray,
+    vec3::Point3,
+};
+
+// Number of buckets along each axis among which the centroids are distributed when looking for the cheapest split.
+const BUCKET_COUNT: usize = 12;
+
+// Nodes with at most this many objects are never split.
+const MIN_LEAF_SIZE: usize = 2;
+
+// Nodes with more objects than this are split even if the surface area heuristic judges a leaf cheaper.
+const MAX_LEAF_SIZE: usize = 8;
+
+// Cost of testing a ray against a child's bounding box, relative to the cost of testing it against an object.
+const TRAVERSAL_COST: f64 = 0.125;
+
+// A bounding volume hierarchy: a binary tree of nested boxes, letting a ray skip every object in a box it misses. Objects with no finite bounding box, such as `Plane`, can't be placed in the tree and are tested individually.
+pub struct Bvh {
+    root: Option<BvhNode>,
+    unbounded: Vec<Arc<dyn Hittable>>,
+    bbox: Aabb,
+}
+
+enum BvhNode {
+    Leaf {
+        bbox: Aabb,
+        objects: Vec<Arc<dyn Hittable>>,
+    },
+    Branch {
+        bbox: Aabb,
+        left: Box<BvhNode>,
+        right: Box<BvhNode>,
+    },
+}
+
+struct Primitive {
+    object: Arc<dyn Hittable>,
+    bbox: Aabb,
+    centroid: Point3,
+}
+
+impl Bvh {
+    pub fn new(list: HittableList) -> Self {
+        let mut bounded = Vec::with_capacity(list.objects.len());
+        let mut unbounded = Vec::new();
+
+        for object in list.objects {
+            let bbox = object.bounding_box();
+            if bbox.is_bounded() {
+                bounded.push(Primitive {
+                    centroid: bbox.centroid(),
+                    object,
+                    bbox,
+                });
+            } else {
+                unbounded.push(object);
+            }
+        }
+
+        let bbox = if unbounded.is_empty() {
+            bounded.iter().fold(Aabb::EMPTY, |bbox, primitive| {
+                Aabb::surrounding(&bbox, &primitive.bbox)
+            })
+        } else {
+            Aabb::UNIVERSE
+        };
+
+        let root = if bounded.is_empty() {
+            None
+        } else {
+            Some(BvhNode::build(bounded))
+        };
+
+        Self {
+            root,
+            unbounded,
+            bbox,
+        }
+    }
+}
+
+impl Hittable for Bvh {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Option<HitRecord> {
+        let mut closest_hit = closest_of(&self.unbounded, ray, ray_t, rng);
+        let closest_so_far = closest_hit.as_ref().map_or(ray_t.max, |hit| hit.t);
+
+        if let Some(root) = &self.root
+            && let Some(hit) = root.hit(ray, &Interval::new(ray_t.min, closest_so_far), rng)
+        {
+            closest_hit = Some(hit);
+        }
+
+        closest_hit
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+impl BvhNode {
+    fn build(mut primitives: Vec<Primitive>) -> Self {
+        let bbox = primitives.iter().fold(Aabb::EMPTY, |bbox, primitive| {
+            Aabb::surrounding(&bbox, &primitive.bbox)
+        });
+        let count = primitives.len();
+
+        if count <= MIN_LEAF_SIZE {
+            return Self::leaf(bbox, primitives);
+        }
+
+        let centroid_bounds = primitives.iter().fold(Aabb::EMPTY, |bounds, primitive| {
+            Aabb::surrounding(
+                &bounds,
+                &Aabb {
+                    x: Interval::new(primitive.centroid.x, primitive.centroid.x),
+                    y: Interval::new(primitive.centroid.y, primitive.centroid.y),
+                    z: Interval::new(primitive.centroid.z, primitive.centroid.z),
+                },
+            )
+        });
+
+        let left = match Self::cheapest_split(&primitives, &bbox, &centroid_bounds) {
+            Some((axis, threshold, cost)) if cost < count as f64 || count > MAX_LEAF_SIZE => {
+                let (left, right): (Vec<_>, Vec<_>) = primitives
+                    .into_iter()
+                    .partition(|primitive| primitive.centroid[axis] < threshold);
+                primitives = right;
+                left
+            }
+            _ if count > MAX_LEAF_SIZE => {
+                // All centroids coincide, so no split separates them: halve the list arbitrarily.
+                primitives.split_off(count / 2)
+            }
+            _ => return Self::leaf(bbox, primitives),
+        };
+
+        BvhNode::Branch {
+            bbox,
+            left: Box::new(Self::build(left)),
+            right: Box::new(Self::build(primitives)),
+        }
+    }
+
+    fn leaf(bbox: Aabb, primitives: Vec<Primitive>) -> Self {
+        BvhNode::Leaf {
+            bbox,
+            objects: primitives
+                .into_iter()
+                .map(|primitive| primitive.object)
+                .collect(),
+        }
+    }
+
+    // Surface area heuristic: the expected cost of a split is proportional to the probability of a ray entering each child (the ratio of its surface area to its parent's) times the number of objects in it. Returns the axis and threshold of the cheapest split found, together with its cost in units of one object intersection.
+    fn cheapest_split(
+        primitives: &[Primitive],
+        bbox: &Aabb,
+        centroid_bounds: &Aabb,
+    ) -> Option<(usize, f64, f64)> {
+        let parent_area = bbox.surface_area();
+        let mut best: Option<(usize, f64, f64)> = None;
+
+        for axis in 0..3 {
+            let extent = centroid_bounds.axis_interval(axis);
+            if extent.size() < 1e-12 {
+                continue;
+            }
+
+            let bucket_of = |centroid: &Point3| {
+                let offset = (centroid[axis] - extent.min) / extent.size();
+                ((offset * BUCKET_COUNT as f64) as usize).min(BUCKET_COUNT - 1)
+            };
+
+            let mut counts = [0usize; BUCKET_COUNT];
+            let mut bounds = [Aabb::EMPTY; BUCKET_COUNT];
+            for primitive in primitives {
+                let b = bucket_of(&primitive.centroid);
+                counts[b] += 1;
+                bounds[b] = Aabb::surrounding(&bounds[b], &primitive.bbox);
+            }
+
+            for split in 1..BUCKET_COUNT {
+                let (left_count, left_bounds) = accumulate(&counts[..split], &bounds[..split]);
+                let (right_count, right_bounds) = accumulate(&counts[split..], &bounds[split..]);
+                if left_count == 0 || right_count == 0 {
+                    continue;
+                }
+
+                let cost = TRAVERSAL_COST
+                    + (left_count as f64 * left_bounds.surface_area()
+                        + right_count as f64 * right_bounds.surface_area())
+                        / parent_area;
+
+                if best.is_none_or(|(_, _, best_cost)| cost < best_cost) {
+                    let threshold = extent.min + extent.size() * split as f64 / BUCKET_COUNT as f64;
+                    best = Some((axis, threshold, cost));
+                }
+            }
+        }
+
+        best
+    }
+
+    fn bbox(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf { bbox, .. } | BvhNode::Branch { bbox, .. } => bbox,
+        }
+    }
+
+    fn hit(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Option<HitRecord> {
+        if !self.bbox().hit(ray, ray_t) {
+            return None;
+        }
+
+        match self {
+            BvhNode::Leaf { objects, .. } => closest_of(objects, ray, ray_t, rng),
+            BvhNode::Branch { left, right, .. } => {
+                let left_hit = left.hit(ray, ray_t, rng);
+                let closest_so_far = left_hit.as_ref().map_or(ray_t.max, |hit| hit.t);
+                right
+                    .hit(ray, &Interval::new(ray_t.min, closest_so_far), rng)
+                    .or(left_hit)
+            }
+        }
+    }
+}
+
+fn accumulate(counts: &[usize], bounds: &[Aabb]) -> (usize, Aabb) {
+    counts.iter().zip(bounds).fold(
+        (0, Aabb::EMPTY),
+        |(total, total_bounds), (count, bounds)| {
+            (total + count, Aabb::surrounding(&total_bounds, bounds))
+        },
+    )
+}
+
+fn closest_of(
+    objects: &[Arc<dyn Hittable>],
+    ray: &Ray,
+    ray_t: &Interval,
+    rng: &mut SmallRng,
+) -> Option<HitRecord> {
+    let mut closest_hit: Option<HitRecord> = None;
+    let mut closest_so_far = ray_t.max;
+
+    for object in objects {
+        if let Some(hit) = object.hit(ray, &Interval::new(ray_t.min, closest_so_far), rng) {
+            closest_so_far = hit.t;
+            closest_hit = Some(hit);
+        }
+    }
+
+    closest_hit
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::{color::Color, hittables::sphere::Sphere, materials::Lambertian, vec3::Direction};
+
+    fn random_spheres(count: usize, rng: &mut SmallRng) -> HittableList {
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let mut list = HittableList::new();
+        for _ in 0..count {
+            let center = Point3::random(-10., 10., rng);
+            let radius = rng.random_range(0.1..1.0);
+            list.add(Arc::new(Sphere::new(center, radius, material.clone())));
+        }
+        list
+    }
+
+    #[test]
+    fn bvh_agrees_with_linear_search() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let list = random_spheres(200, &mut rng);
+        let objects = list.objects.clone();
+        let bvh = Bvh::new(list);
+        let linear = HittableList { objects };
+
+        for _ in 0..1000 {
+            let origin = Point3::random(-15., 15., &mut rng);
+            let ray = Ray::new(origin, Direction::random_unit(&mut rng));
+            let ray_t = Interval::new(0.001, f64::INFINITY);
+
+            let expected = linear.hit(&ray, &ray_t, &mut rng).map(|hit| hit.t);
+            let actual = bvh.hit(&ray, &ray_t, &mut rng).map(|hit| hit.t);
+            match (expected, actual) {
+                (None, None) => {}
+                (Some(a), Some(b)) => assert!((a - b).abs() < 1e-9),
+                _ => panic!("BVH and linear search disagree: {expected:?} vs {actual:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn bvh_box_encloses_objects() {
+        let mut rng = SmallRng::seed_from_u64(11);
+        let list = random_spheres(50, &mut rng);
+        let expected = list.bounding_box();
+        let bvh = Bvh::new(list);
+        assert_eq!(bvh.bounding_box(), expected);
+    }
+}
@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable, sphere::Sphere, tube::Tube},
+    interval::Interval,
+    materials::Material,
+    ray::Ray,
+    vec3::{Direction, Point3},
+};
+
+// A tube with rounded ends: the points within `radius` of the line segment from `center_of_base` to `center_of_base + axis`. It's made of a `Tube` and the outer halves of a sphere at each end.
+pub struct Capsule {
+    tube: Tube,
+    bottom: Sphere,
+    top: Sphere,
+    axis: Direction, // Unit vector from the bottom end to the top.
+    base: Point3,
+    top_center: Point3,
+    radius: f64,
+}
+
+impl Capsule {
+    pub fn new(
+        center_of_base: Point3,
+        axis: Direction,
+        radius: f64,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        assert!(1e-8 < axis.length(), "Axis vector is too small");
+        let top_center = center_of_base + axis;
+        Self {
+            tube: Tube::new(center_of_base, axis, radius, material.clone()),
+            bottom: Sphere::new(center_of_base, radius, material.clone()),
+            top: Sphere::new(top_center, radius, material),
+            axis: axis.normalize(),
+            base: center_of_base,
+            top_center,
+            radius,
+        }
+    }
+}
+
+impl Hittable for Capsule {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Option<HitRecord> {
+        // Each end is only the half of its sphere that lies beyond the tube.
+        let bottom = self
+            .bottom
+            .all_hits(ray, ray_t, rng)
+            .into_iter()
+            .find(|hit| (hit.point - self.base).dot(&self.axis) <= 0.);
+        let top = self
+            .top
+            .all_hits(ray, ray_t, rng)
+            .into_iter()
+            .find(|hit| (hit.point - self.top_center).dot(&self.axis) >= 0.);
+        let side = self.tube.hit(ray, ray_t, rng);
+
+        [side, bottom, top]
+            .into_iter()
+            .flatten()
+            .min_by(|a, b| a.t.total_cmp(&b.t))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let extent = Direction::new(self.radius, self.radius, self.radius);
+        Aabb::surrounding(
+            &Aabb::around(self.base, extent),
+            &Aabb::around(self.top_center, extent),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{color::Color, materials::Lambertian, vec3};
+
+    #[test]
+    fn rounded_ends() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        // Lying along the x axis from -1 to 1, with radius 0.5.
+        let capsule = Capsule::new(
+            Point3::new(-1., 0., 0.),
+            Direction::new(2., 0., 0.),
+            0.5,
+            material,
+        );
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        // End on, through both hemispheres.
+        let ray = Ray::new(Point3::new(5., 0., 0.), Direction::new(-1., 0., 0.));
+        let hits = capsule.all_hits(&ray, &ray_t, &mut rng);
+        let xs: Vec<f64> = hits.iter().map(|hit| hit.point.x).collect();
+        assert_eq!(xs.len(), 2);
+        assert!((xs[0] - 1.5).abs() < 1e-9 && (xs[1] + 1.5).abs() < 1e-9);
+
+        // Down onto the curve of an end, which is further than the tube would reach.
+        let ray = Ray::new(Point3::new(1.3, 5., 0.), Direction::new(0., -1., 0.));
+        let hit = capsule.hit(&ray, &ray_t, &mut rng).unwrap();
+        assert!((hit.point.y - 0.4).abs() < 1e-9);
+        assert!(vec3::approx_eq(
+            hit.outward_normal(),
+            Direction::new(0.6, 0.8, 0.),
+            1e-9
+        ));
+
+        // From inside, out through the side.
+        let ray = Ray::new(Point3::new(0., 0., 0.), Direction::new(0., 0., 1.));
+        let hit = capsule.hit(&ray, &ray_t, &mut rng).unwrap();
+        assert!((hit.t - 0.5).abs() < 1e-9);
+        assert!(!hit.front_face);
+    }
+}
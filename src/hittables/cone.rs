@@ -0,0 +1,212 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable},
+    interval::Interval,
+    materials::Material,
+    ray::Ray,
+    vec3::{self, Direction, Point3},
+};
+
+// The open, slanted side of a cone, cut off square at both ends: like a `Tube`, but with the radius changing steadily from `base_radius` at the base to `top_radius` at the top. Either radius can be 0, to make a pointed cone.
+pub struct Cone {
+    pub center_of_base: Point3,
+    pub axis: Direction,
+    pub base_radius: f64,
+    pub top_radius: f64,
+    pub height: f64,
+    pub material: Arc<dyn Material>,
+    reference: [Direction; 2], // Perpendicular to the axis: angles around it are measured from the first towards the second.
+}
+
+impl Cone {
+    pub fn new(
+        center_of_base: Point3,
+        mut axis: Direction,
+        base_radius: f64,
+        top_radius: f64,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        assert!(1e-8 < axis.length(), "Axis vector is too small");
+        assert!(
+            0. <= base_radius && 0. <= top_radius,
+            "Radius must not be negative"
+        );
+        assert!(1e-8 < base_radius.max(top_radius), "Radius is too small");
+
+        let height = axis.length();
+        axis = axis.normalize();
+        Cone {
+            center_of_base,
+            axis,
+            base_radius,
+            top_radius,
+            height,
+            material,
+            reference: vec3::orthonormal_basis_2d(&axis),
+        }
+    }
+
+    // How much the radius grows per unit of height.
+    fn slope(&self) -> f64 {
+        (self.top_radius - self.base_radius) / self.height
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Option<HitRecord> {
+        let origin_to_center = ray.origin - self.center_of_base;
+        let axis = self.axis;
+        let slope = self.slope();
+
+        let direction_dot_axis = ray.direction.dot(&axis);
+        let origin_to_center_dot_axis = origin_to_center.dot(&axis);
+
+        let direction_perp = ray.direction - direction_dot_axis * axis;
+        let origin_to_center_perp = origin_to_center - origin_to_center_dot_axis * axis;
+
+        // The distance from the axis equals the radius at that height: |perp|² = (base_radius + slope * height)².
+        let radius_at_origin = self.base_radius + slope * origin_to_center_dot_axis;
+        let radius_change = slope * direction_dot_axis;
+        let a = direction_perp.dot(&direction_perp) - radius_change * radius_change;
+        let h = direction_perp.dot(&origin_to_center_perp) - radius_at_origin * radius_change;
+        let c =
+            origin_to_center_perp.dot(&origin_to_center_perp) - radius_at_origin * radius_at_origin;
+
+        // When the ray is parallel to a line on the cone's surface, the equation is linear and there's only one root.
+        let roots = if a.abs() < 1e-12 {
+            if h.abs() < 1e-12 {
+                return None;
+            }
+            [-c / (2. * h), f64::INFINITY]
+        } else {
+            let discriminant = h * h - a * c;
+            if discriminant < 0.0 {
+                return None;
+            }
+            let sqrt_d = discriminant.sqrt();
+            let (t0, t1) = ((-h - sqrt_d) / a, (-h + sqrt_d) / a);
+            [t0.min(t1), t0.max(t1)]
+        };
+
+        for t in roots {
+            if !ray_t.surrounds(t) {
+                continue;
+            }
+
+            // The equation also describes the mirror-image cone on the far side of the apex, but that lies outside the range of heights.
+            let point = ray.at(t);
+            let height_along_axis = (point - self.center_of_base).dot(&axis);
+            if height_along_axis < 0.0 || height_along_axis > self.height {
+                continue;
+            }
+
+            let projection = self.center_of_base + height_along_axis * axis;
+            let radial = point - projection;
+            let radial = if radial.near_zero() {
+                self.reference[0] // At the apex.
+            } else {
+                radial.normalize()
+            };
+            // Tilted towards the narrow end, as the surface slopes.
+            let outward_normal = (radial - slope * axis).normalize();
+
+            // u is the angle around the axis as a fraction of a full turn, v the height as a fraction of the cone's length.
+            let [reference_u, reference_v] = &self.reference;
+            let angle = radial.dot(reference_v).atan2(radial.dot(reference_u));
+            let u = angle.rem_euclid(2. * PI) / (2. * PI);
+            let v = height_along_axis / self.height;
+
+            return Some(
+                HitRecord::new(point, outward_normal, t, self.material.clone(), ray)
+                    .with_uv(u, v)
+                    .with_tangent(axis.cross(&radial)),
+            );
+        }
+
+        None
+    }
+
+    // The cone lies within the boxes of its two end circles.
+    fn bounding_box(&self) -> Aabb {
+        let extent = |radius: f64| {
+            let extent = |a: f64| radius * (1. - a * a).max(0.).sqrt();
+            Direction::new(
+                extent(self.axis.x),
+                extent(self.axis.y),
+                extent(self.axis.z),
+            )
+        };
+        let top = self.center_of_base + self.height * self.axis;
+        Aabb::surrounding(
+            &Aabb::around(self.center_of_base, extent(self.base_radius)),
+            &Aabb::around(top, extent(self.top_radius)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{color::Color, materials::Lambertian};
+
+    #[test]
+    fn pointed_cone() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        // Standing on the origin, 2 high, with radius 1 at the base.
+        let cone = Cone::new(
+            Point3::new(0., 0., 0.),
+            Direction::new(0., 2., 0.),
+            1.,
+            0.,
+            material,
+        );
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        // Halfway up, the radius is 0.5, and the normal leans up at the same angle as the side leans in.
+        let ray = Ray::new(Point3::new(5., 1., 0.), Direction::new(-1., 0., 0.));
+        let hit = cone.hit(&ray, &ray_t, &mut rng).unwrap();
+        assert!((hit.t - 4.5).abs() < 1e-9);
+        assert!((hit.v - 0.5).abs() < 1e-9);
+        let expected = Direction::new(2., 1., 0.).normalize();
+        assert!(vec3::approx_eq(hit.outward_normal(), expected, 1e-9));
+
+        // Straight down the axis, the ray goes in at the apex and comes out through the open base.
+        let ray = Ray::new(Point3::new(0., 5., 0.), Direction::new(0., -1., 0.));
+        let hit = cone.hit(&ray, &ray_t, &mut rng).unwrap();
+        assert!((hit.t - 3.).abs() < 1e-9);
+
+        // Above the apex, the mirror-image cone isn't there.
+        let ray = Ray::new(Point3::new(5., 3., 0.), Direction::new(-1., 0., 0.));
+        assert!(cone.hit(&ray, &ray_t, &mut rng).is_none());
+    }
+
+    #[test]
+    fn ray_parallel_to_the_side() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let cone = Cone::new(
+            Point3::new(0., 0., 0.),
+            Direction::new(0., 1., 0.),
+            1.,
+            0.,
+            material,
+        );
+        // Parallel to the side through (-1, 0, 0) and the apex, so, coming up through the open base, it meets the cone only once, on the opposite side.
+        let ray = Ray::new(Point3::new(-0.5, -1., 0.), Direction::new(1., 1., 0.));
+        let hit = cone
+            .hit(&ray, &Interval::new(0.001, f64::INFINITY), &mut rng)
+            .unwrap();
+        assert!(vec3::approx_eq(
+            hit.point,
+            Point3::new(0.75, 0.25, 0.),
+            1e-9
+        ));
+    }
+}
@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable},
+    interval::Interval,
+    ray::Ray,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    Union,        // Inside either shape.
+    Intersection, // Inside both shapes.
+    Difference,   // Inside the first shape but not the second.
+}
+
+impl Operation {
+    fn contains(&self, inside_a: bool, inside_b: bool) -> bool {
+        match self {
+            Operation::Union => inside_a || inside_b,
+            Operation::Intersection => inside_a && inside_b,
+            Operation::Difference => inside_a && !inside_b,
+        }
+    }
+}
+
+// Constructive solid geometry: a solid made by combining two others. Both must be closed, so that every ray alternately enters and leaves them. Each part of the surface keeps the material of the shape it comes from, so the hollow that a `Difference` carves out has the material of the second shape.
+pub struct Csg {
+    a: Arc<dyn Hittable>,
+    b: Arc<dyn Hittable>,
+    operation: Operation,
+}
+
+impl Csg {
+    pub fn new(a: Arc<dyn Hittable>, b: Arc<dyn Hittable>, operation: Operation) -> Self {
+        Self { a, b, operation }
+    }
+
+    pub fn union(a: Arc<dyn Hittable>, b: Arc<dyn Hittable>) -> Self {
+        Self::new(a, b, Operation::Union)
+    }
+
+    pub fn intersection(a: Arc<dyn Hittable>, b: Arc<dyn Hittable>) -> Self {
+        Self::new(a, b, Operation::Intersection)
+    }
+
+    pub fn difference(a: Arc<dyn Hittable>, b: Arc<dyn Hittable>) -> Self {
+        Self::new(a, b, Operation::Difference)
+    }
+
+    // Walk along the ray's whole line, keeping track of whether we're inside each shape. A hit on either shape is a hit on the combination wherever it changes whether we're inside the combination.
+    fn boundary(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Vec<HitRecord> {
+        let mut events: Vec<(HitRecord, bool)> = self
+            .a
+            .all_hits(ray, &Interval::FULL, rng)
+            .into_iter()
+            .map(|hit| (hit, true))
+            .chain(
+                self.b
+                    .all_hits(ray, &Interval::FULL, rng)
+                    .into_iter()
+                    .map(|hit| (hit, false)),
+            )
+            .collect();
+        events.sort_by(|(x, _), (y, _)| x.t.total_cmp(&y.t));
+
+        let mut inside_a = false;
+        let mut inside_b = false;
+        let mut boundary = Vec::new();
+        for (mut hit, is_a) in events {
+            let was_inside = self.operation.contains(inside_a, inside_b);
+
+            // A hit on the outward-facing side of a surface is a way in.
+            if is_a {
+                inside_a = hit.front_face;
+            } else {
+                inside_b = hit.front_face;
+            }
+
+            let is_inside = self.operation.contains(inside_a, inside_b);
+            if was_inside == is_inside || !ray_t.surrounds(hit.t) {
+                continue;
+            }
+
+            // Where the combination is entered through a way out of one of the shapes (e.g. the hollow carved out by a `Difference`), that surface faces the other way as part of the combination. Flipping its outward normal leaves `normal`, which faces the ray, unchanged, and the bitangent flips to keep the tangent frame right-handed.
+            if hit.front_face != is_inside {
+                hit.front_face = is_inside;
+                hit.bitangent = -hit.bitangent;
+            }
+            boundary.push(hit);
+        }
+        boundary
+    }
+}
+
+impl Hittable for Csg {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Option<HitRecord> {
+        self.boundary(ray, ray_t, rng).into_iter().next()
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let a = self.a.bounding_box();
+        let b = self.b.bounding_box();
+        match self.operation {
+            Operation::Union => Aabb::surrounding(&a, &b),
+            Operation::Intersection => Aabb::overlap(&a, &b),
+            Operation::Difference => a,
+        }
+    }
+
+    fn all_hits(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Vec<HitRecord> {
+        self.boundary(ray, ray_t, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{
+        color::Color,
+        hittables::{cube::Cube, quad::Quad, sphere::Sphere},
+        materials::Lambertian,
+        vec3::{self, Direction, Point3},
+    };
+
+    fn sphere(x: f64, radius: f64) -> Arc<dyn Hittable> {
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        Arc::new(Sphere::new(Point3::new(x, 0., 0.), radius, material))
+    }
+
+    // Distances along a ray down the x axis from x = 10 to where it hits the shape.
+    fn hits(shape: &dyn Hittable) -> Vec<f64> {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let ray = Ray::new(Point3::new(10., 0., 0.), Direction::new(-1., 0., 0.));
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        let hits = shape.all_hits(&ray, &ray_t, &mut rng);
+        assert_eq!(
+            hits.first().map(|hit| hit.t),
+            shape.hit(&ray, &ray_t, &mut rng).map(|hit| hit.t)
+        );
+        hits.iter().map(|hit| 10. - hit.t).collect()
+    }
+
+    fn assert_close(actual: Vec<f64>, expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn operations_on_overlapping_spheres() {
+        // Along the x axis, the first sphere covers [-1, 1], the second [0, 2].
+        let (a, b) = (sphere(0., 1.), sphere(1., 1.));
+        assert_close(hits(&Csg::union(a.clone(), b.clone())), &[2., -1.]);
+        assert_close(hits(&Csg::intersection(a.clone(), b.clone())), &[1., 0.]);
+        assert_close(hits(&Csg::difference(a.clone(), b.clone())), &[0., -1.]);
+        assert_close(hits(&Csg::difference(b, a)), &[2., 1.]);
+    }
+
+    #[test]
+    fn carved_surface_faces_out_of_the_solid() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let cube = Arc::new(Cube::new(Point3::new(0., 0., 0.), 1., material));
+        let carved = Csg::difference(cube, sphere(1., 1.));
+
+        // The ray enters the solid through the sphere's surface, from inside the sphere.
+        let ray = Ray::new(Point3::new(10., 0., 0.), Direction::new(-1., 0., 0.));
+        let hit = carved
+            .hit(&ray, &Interval::new(0.001, f64::INFINITY), &mut rng)
+            .unwrap();
+        assert!((hit.point.x - 0.).abs() < 1e-9);
+        assert!(hit.front_face);
+        assert!(vec3::approx_eq(
+            hit.outward_normal(),
+            Direction::new(1., 0., 0.),
+            1e-9
+        ));
+        assert!(vec3::approx_eq(
+            hit.tangent.cross(&hit.bitangent),
+            hit.outward_normal(),
+            1e-9
+        ));
+    }
+
+    #[test]
+    fn distant_surfaces_are_found_once() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let ray = Ray::new(Point3::new(0., 0., 0.), Direction::new(0., 0., 1.));
+        // Operands are searched along the whole line, so a surface far behind the ray counts too.
+        for z in [1e11, -1e11] {
+            let quad = Quad::new(
+                Point3::new(-1., -1., z),
+                Direction::new(2., 0., 0.),
+                Direction::new(0., 2., 0.),
+                material.clone(),
+            );
+            let hits = quad.all_hits(&ray, &Interval::FULL, &mut rng);
+            assert_eq!(hits.len(), 1);
+            assert!((hits[0].t - z).abs() < 1e-3 * z.abs());
+        }
+    }
+
+    #[test]
+    fn nested_operations() {
+        // A shell: the first sphere minus a smaller one inside it, then cut in half by a third.
+        let shell = Arc::new(Csg::difference(sphere(0., 2.), sphere(0., 1.)));
+        assert_close(hits(shell.as_ref()), &[2., 1., -1., -2.]);
+        let half = Csg::intersection(shell, sphere(3., 3.));
+        assert_close(hits(&half), &[2., 1.]);
+    }
+}
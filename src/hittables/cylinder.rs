@@ -3,11 +3,12 @@ use std::sync::Arc;
 use rand::rngs::SmallRng;
 
 use crate::{
+    aabb::Aabb,
     hittables::{HitRecord, Hittable, disk::Disk, tube::Tube},
     interval::Interval,
     materials::Material,
     ray::Ray,
-    vec3::{Direction, Point3},
+    vec3::{self, Direction, Point3},
 };
 
 pub struct Cylinder {
@@ -26,16 +27,11 @@ impl Cylinder {
         material_bottom: Arc<dyn Material>,
     ) -> Self {
         assert!(1e-8 < axis.length(), "Axis vector is too small");
-        let [u, v] = orthonormal_basis_2d(&axis.normalize());
+        let [u, v] = vec3::orthonormal_basis_2d(&axis.normalize());
 
-        let top = Arc::new(Disk::new(
-            center_of_base + axis,
-            radius,
-            u.clone(),
-            v.clone(),
-            material_top,
-        ));
-        let bottom = Arc::new(Disk::new(center_of_base, radius, u, v, material_bottom));
+        let top = Arc::new(Disk::new(center_of_base + axis, radius, u, v, material_top));
+        // Swap the spanning vectors so that the bottom's normal points out of the cylinder, away from the top.
+        let bottom = Arc::new(Disk::new(center_of_base, radius, v, u, material_bottom));
         let tube = Arc::new(Tube::new(center_of_base, axis, radius, material_tube));
 
         Self {
@@ -70,17 +66,51 @@ impl Hittable for Cylinder {
 
         closest_hit
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.tube.bounding_box()
+    }
 }
 
-fn orthonormal_basis_2d(axis: &Direction) -> [Direction; 2] {
-    let w = axis.normalize();
-    let a = if w.x.abs() > 0.9 {
-        Direction::new(0.0, 1.0, 0.0)
-    } else {
-        Direction::new(1.0, 0.0, 0.0)
-    };
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{color::Color, materials::Lambertian};
 
-    let v = w.cross(&a).normalize();
-    let u = w.cross(&v);
-    [u, v]
+    #[test]
+    fn caps_face_away_from_the_cylinder() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let center_of_base = Point3::new(1., -2., 0.5);
+        let axis = Direction::new(0.4, 2., -0.8);
+        let cylinder = Cylinder::new(
+            center_of_base,
+            axis,
+            0.5,
+            material.clone(),
+            material.clone(),
+            material,
+        );
+        let middle = center_of_base + 0.5 * axis;
+
+        // Rays along the axis from beyond each end, hitting the top and the bottom from outside.
+        for (origin, direction) in [(middle + 2. * axis, -axis), (middle - 2. * axis, axis)] {
+            let hit = cylinder
+                .hit(
+                    &Ray::new(origin, direction),
+                    &Interval::new(0.001, f64::INFINITY),
+                    &mut rng,
+                )
+                .expect("Ray along the axis should hit a cap");
+            assert!(hit.front_face);
+            let outward = hit.outward_normal();
+            assert!(vec3::approx_eq(
+                outward,
+                (hit.point - middle).normalize(),
+                1e-9
+            ));
+        }
+    }
 }
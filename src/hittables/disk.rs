@@ -1,8 +1,9 @@
-use std::sync::Arc;
+use std::{f64::consts::PI, sync::Arc};
 
-use rand::rngs::SmallRng;
+use rand::{Rng, rngs::SmallRng};
 
 use crate::{
+    aabb::Aabb,
     hittables::{HitRecord, Hittable},
     interval::Interval,
     materials::Material,
@@ -55,6 +56,13 @@ impl Disk {
     }
 }
 
+impl Disk {
+    // The area of a unit square in plane coordinates, which is less than 1 if `u` and `v` aren't at right angles. The disk is then really an ellipse.
+    fn skew(&self) -> f64 {
+        self.u.cross(&self.v).length()
+    }
+}
+
 impl Hittable for Disk {
     fn hit(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Option<HitRecord> {
         let denominator = self.normal.dot(&ray.direction);
@@ -80,12 +88,40 @@ impl Hittable for Disk {
             return None;
         }
 
-        Some(HitRecord::new(
-            intersection,
-            self.normal,
-            t,
-            self.material.clone(),
-            ray,
-        ))
+        // Polar coordinates: u is the angle from `self.u` towards `self.v` as a fraction of a full turn, v the distance from the center as a fraction of the radius.
+        let angle = beta.atan2(alpha);
+        let u = angle.rem_euclid(2. * PI) / (2. * PI);
+        let v = (alpha * alpha + beta * beta).sqrt() / self.radius;
+        let tangent = -angle.sin() * self.u + angle.cos() * self.v;
+
+        Some(
+            HitRecord::new(intersection, self.normal, t, self.material.clone(), ray)
+                .with_uv(u, v)
+                .with_tangent(tangent),
+        )
+    }
+
+    // Along each world axis, a circle of radius r extends r * sin(angle between axis and normal) either side of its center.
+    fn bounding_box(&self) -> Aabb {
+        let extent = |n: f64| self.radius * (1. - n * n).max(0.).sqrt();
+        Aabb::around(
+            self.point,
+            Direction::new(
+                extent(self.normal.x),
+                extent(self.normal.y),
+                extent(self.normal.z),
+            ),
+        )
+    }
+
+    fn area(&self) -> f64 {
+        PI * self.radius * self.radius / self.skew()
+    }
+
+    fn sample_surface(&self, _time: f64, rng: &mut SmallRng) -> Option<Point3> {
+        // A uniformly random point in the unit disk, stretched to the disk's plane coordinates.
+        let radius = rng.random::<f64>().sqrt() * self.radius / self.skew();
+        let angle = rng.random_range(0.0..2. * PI);
+        Some(self.point + radius * (angle.cos() * self.u + angle.sin() * self.v))
     }
 }
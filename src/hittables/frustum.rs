@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable, cone::Cone, disk::Disk},
+    interval::Interval,
+    materials::Material,
+    ray::Ray,
+    vec3::{self, Direction, Point3},
+};
+
+// A solid cone with its tip cut off: a `Cone` closed by a disk at each end, as a `Cylinder` is a `Tube` closed by two disks. If one of the radii is 0, that end comes to a point and has no disk.
+pub struct Frustum {
+    side: Arc<Cone>,
+    top: Option<Arc<Disk>>,
+    bottom: Option<Arc<Disk>>,
+}
+
+impl Frustum {
+    pub fn new(
+        center_of_base: Point3,
+        axis: Direction,
+        base_radius: f64,
+        top_radius: f64,
+        material_side: Arc<dyn Material>,
+        material_top: Arc<dyn Material>,
+        material_bottom: Arc<dyn Material>,
+    ) -> Self {
+        assert!(1e-8 < axis.length(), "Axis vector is too small");
+        let [u, v] = vec3::orthonormal_basis_2d(&axis.normalize());
+
+        let side = Arc::new(Cone::new(
+            center_of_base,
+            axis,
+            base_radius,
+            top_radius,
+            material_side,
+        ));
+        let top = (1e-8 < top_radius).then(|| {
+            Arc::new(Disk::new(
+                center_of_base + axis,
+                top_radius,
+                u,
+                v,
+                material_top,
+            ))
+        });
+        // Swap the spanning vectors so that the bottom's normal points out of the frustum, away from the top.
+        let bottom = (1e-8 < base_radius).then(|| {
+            Arc::new(Disk::new(
+                center_of_base,
+                base_radius,
+                v,
+                u,
+                material_bottom,
+            ))
+        });
+
+        Self { side, top, bottom }
+    }
+}
+
+impl Hittable for Frustum {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Option<HitRecord> {
+        let mut closest_hit: Option<HitRecord> = None;
+
+        let ends = [&self.top, &self.bottom]
+            .into_iter()
+            .flatten()
+            .map(|end| end as &dyn Hittable);
+        for part in std::iter::once(&self.side as &dyn Hittable).chain(ends) {
+            if let Some(current_hit) = part.hit(ray, ray_t, rng) {
+                match &closest_hit {
+                    None => {
+                        closest_hit = Some(current_hit);
+                    }
+                    Some(previous_hit) if current_hit.t < previous_hit.t => {
+                        closest_hit = Some(current_hit);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        closest_hit
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.side.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{color::Color, materials::Lambertian};
+
+    #[test]
+    fn rays_through_the_ends_and_the_side() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let gray = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let frustum = Frustum::new(
+            Point3::new(0., 0., 0.),
+            Direction::new(0., 1., 0.),
+            2.,
+            1.,
+            gray.clone(),
+            gray.clone(),
+            gray,
+        );
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        let ray = Ray::new(Point3::new(0.5, 5., 0.), Direction::new(0., -1., 0.));
+
+        // Straight down, the ray passes in through the top and out through the bottom.
+        let hits = frustum.all_hits(&ray, &ray_t, &mut rng);
+        let heights: Vec<f64> = hits.iter().map(|hit| hit.point.y).collect();
+        assert_eq!(heights.len(), 2);
+        assert!((heights[0] - 1.).abs() < 1e-9 && heights[1].abs() < 1e-9);
+        assert!(hits[0].front_face && !hits[1].front_face);
+        assert!(vec3::approx_eq(
+            hits[1].outward_normal(),
+            Direction::new(0., -1., 0.),
+            1e-9
+        ));
+
+        // Just outside the top, the ray passes down the side.
+        let ray = Ray::new(Point3::new(1.2, 5., 0.), Direction::new(0., -1., 0.));
+        let hit = frustum.hit(&ray, &ray_t, &mut rng).unwrap();
+        assert!((hit.point.y - 0.8).abs() < 1e-9);
+    }
+}
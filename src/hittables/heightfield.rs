@@ -0,0 +1,346 @@
+use std::sync::Arc;
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{
+        HitRecord, Hittable,
+        triangle::{self, TriangleHit},
+    },
+    interval::Interval,
+    loader::image::linear_to_srgb,
+    materials::Material,
+    ray::Ray,
+    textures::ImageTexture,
+    vec3::{Direction, Point3},
+};
+
+// Terrain: a surface over a rectangle in the xz plane, with a height at each point of a regular grid. Each grid cell is split into two triangles, with normals interpolated between the vertices so that the surface looks smooth. Rather than testing every triangle, a ray walks across the grid from cell to cell, testing only the cells it passes over, and only if it's within the range of heights there.
+pub struct Heightfield {
+    corner: Point3,      // The corner with the least x and z, at the base height.
+    cell_size: [f64; 2], // Along x and z.
+    columns: usize,      // Cells along x.
+    rows: usize,         // Cells along z.
+    heights: Vec<f64>,   // Absolute heights of the grid points, row by row, from the least z.
+    normals: Vec<Direction>,
+    cell_heights: Vec<Interval>, // The range of heights over each cell.
+    bbox: Aabb,
+    material: Arc<dyn Material>,
+}
+
+impl Heightfield {
+    // Sample `height(x, z)` on a grid of `columns` by `rows` cells covering `width` along x and `depth` along z from `corner`. Heights are measured up from `corner`.
+    pub fn from_fn(
+        corner: Point3,
+        width: f64,
+        depth: f64,
+        columns: usize,
+        rows: usize,
+        height: impl Fn(f64, f64) -> f64,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        assert!(1e-8 < width && 1e-8 < depth, "Size is too small");
+        assert!(0 < columns && 0 < rows, "Grid must have at least one cell");
+        let (dx, dz) = (width / columns as f64, depth / rows as f64);
+        let heights = (0..=rows)
+            .flat_map(|row| (0..=columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let x = corner.x + column as f64 * dx;
+                let z = corner.z + row as f64 * dz;
+                corner.y + height(x, z)
+            })
+            .collect();
+        Self::new(corner, [dx, dz], columns, rows, heights, material)
+    }
+
+    // One grid point per pixel, with the image seen from above: its top row along the far (least z) edge, and its left column along the least x. Black is at the height of `corner` and white `max_height` above it.
+    pub fn from_image(
+        corner: Point3,
+        width: f64,
+        depth: f64,
+        max_height: f64,
+        image: &ImageTexture,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        assert!(
+            2 <= image.width() && 2 <= image.height(),
+            "Image must be at least 2 pixels in each direction"
+        );
+        let (columns, rows) = (image.width() - 1, image.height() - 1);
+        // Loaded images are converted to linear light, but height maps store heights directly, so undo the conversion.
+        let heights = (0..=rows)
+            .flat_map(|row| (0..=columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let pixel = image.pixel(column, row);
+                let gray = (pixel.r + pixel.g + pixel.b) / 3.;
+                corner.y + max_height * linear_to_srgb(gray)
+            })
+            .collect();
+        let cell_size = [width / columns as f64, depth / rows as f64];
+        Self::new(corner, cell_size, columns, rows, heights, material)
+    }
+
+    fn new(
+        corner: Point3,
+        cell_size: [f64; 2],
+        columns: usize,
+        rows: usize,
+        heights: Vec<f64>,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        let mut heightfield = Self {
+            corner,
+            cell_size,
+            columns,
+            rows,
+            heights,
+            normals: Vec::new(),
+            cell_heights: Vec::new(),
+            bbox: Aabb::EMPTY,
+            material,
+        };
+
+        heightfield.normals = (0..=rows)
+            .flat_map(|row| (0..=columns).map(move |column| (column, row)))
+            .map(|(column, row)| heightfield.vertex_normal(column, row))
+            .collect();
+        heightfield.cell_heights = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let corners = heightfield.cell_corners(column, row);
+                corners.iter().fold(Interval::EMPTY, |range, &y| {
+                    Interval::enclosing(&range, &Interval::new(y, y))
+                })
+            })
+            .collect();
+
+        let range = heightfield
+            .cell_heights
+            .iter()
+            .fold(Interval::EMPTY, |range, cell| {
+                Interval::enclosing(&range, cell)
+            });
+        heightfield.bbox = Aabb::new(
+            Interval::new(corner.x, corner.x + columns as f64 * cell_size[0]),
+            range,
+            Interval::new(corner.z, corner.z + rows as f64 * cell_size[1]),
+        );
+        heightfield
+    }
+
+    fn height(&self, column: usize, row: usize) -> f64 {
+        self.heights[row * (self.columns + 1) + column]
+    }
+
+    fn point(&self, column: usize, row: usize) -> Point3 {
+        Point3::new(
+            self.corner.x + column as f64 * self.cell_size[0],
+            self.height(column, row),
+            self.corner.z + row as f64 * self.cell_size[1],
+        )
+    }
+
+    // The heights at the corners of a cell.
+    fn cell_corners(&self, column: usize, row: usize) -> [f64; 4] {
+        [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(i, j)| self.height(column + i, row + j))
+    }
+
+    // The normal to the smooth surface through the grid points, from the slope between the neighbouring points (or the point itself, at the edges).
+    fn vertex_normal(&self, column: usize, row: usize) -> Direction {
+        let (left, right) = (column.saturating_sub(1), (column + 1).min(self.columns));
+        let (near, far) = (row.saturating_sub(1), (row + 1).min(self.rows));
+        let slope_x = (self.height(right, row) - self.height(left, row))
+            / ((right - left) as f64 * self.cell_size[0]);
+        let slope_z = (self.height(column, far) - self.height(column, near))
+            / ((far - near) as f64 * self.cell_size[1]);
+        Direction::new(-slope_x, 1., -slope_z).normalize()
+    }
+
+    // Each cell is split along the diagonal from (column + 1, row) to (column, row + 1), with both triangles counterclockwise seen from above, so that their normals point up.
+    fn hit_cell(
+        &self,
+        column: usize,
+        row: usize,
+        ray: &Ray,
+        ray_t: &Interval,
+    ) -> Option<(TriangleHit, [(usize, usize); 3])> {
+        let triangles = [
+            [(column, row), (column, row + 1), (column + 1, row)],
+            [(column + 1, row), (column, row + 1), (column + 1, row + 1)],
+        ];
+        triangles
+            .into_iter()
+            .filter_map(|indices| {
+                let vertices = indices.map(|(c, r)| self.point(c, r));
+                triangle::intersect(&vertices, ray, ray_t).map(|hit| (hit, indices))
+            })
+            .min_by(|(a, _), (b, _)| a.t.total_cmp(&b.t))
+    }
+
+    fn record(&self, ray: &Ray, hit: TriangleHit, indices: [(usize, usize); 3]) -> HitRecord {
+        let normals = indices.map(|(c, r)| self.normals[r * (self.columns + 1) + c]);
+        let outward_normal = triangle::interpolate(&normals, hit.beta, hit.gamma);
+        let point = ray.at(hit.t);
+
+        // The whole heightfield is mapped onto the unit square as seen from above, the same way round as `from_image` reads its image.
+        let u = (point.x - self.corner.x) / (self.columns as f64 * self.cell_size[0]);
+        let v = 1. - (point.z - self.corner.z) / (self.rows as f64 * self.cell_size[1]);
+        HitRecord::new(point, outward_normal, hit.t, self.material.clone(), ray)
+            .with_uv(u.clamp(0., 1.), v.clamp(0., 1.))
+            .with_tangent(Direction::new(1., 0., 0.))
+    }
+}
+
+impl Hittable for Heightfield {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Option<HitRecord> {
+        let span = self.bbox.hit_interval(ray, ray_t)?;
+
+        // Walk across the grid cell by cell (a 2D digital differential analyzer), starting from the cell where the ray enters the box, and keeping track of the distance along the ray to the next boundary between columns and between rows.
+        let start = ray.at(span.min);
+        let cell = |coordinate: f64, corner: f64, size: f64, count: usize| {
+            (((coordinate - corner) / size).floor().max(0.) as usize).min(count - 1)
+        };
+        let mut column = cell(start.x, self.corner.x, self.cell_size[0], self.columns);
+        let mut row = cell(start.z, self.corner.z, self.cell_size[1], self.rows);
+
+        let axis = |direction: f64, origin: f64, corner: f64, size: f64, index: usize| {
+            if direction.abs() < 1e-12 {
+                return (0, f64::INFINITY, f64::INFINITY);
+            }
+            let step = if direction > 0. { 1 } else { -1 };
+            let boundary = corner + (index as f64 + if direction > 0. { 1. } else { 0. }) * size;
+            (
+                step,
+                (boundary - origin) / direction,
+                size / direction.abs(),
+            )
+        };
+        let (step_x, mut next_x, delta_x) = axis(
+            ray.direction.x,
+            ray.origin.x,
+            self.corner.x,
+            self.cell_size[0],
+            column,
+        );
+        let (step_z, mut next_z, delta_z) = axis(
+            ray.direction.z,
+            ray.origin.z,
+            self.corner.z,
+            self.cell_size[1],
+            row,
+        );
+
+        let mut entry = span.min;
+        loop {
+            let exit = next_x.min(next_z).min(span.max);
+
+            // Skip the cell unless the ray's height while over it overlaps the heights of the surface there.
+            let heights = &self.cell_heights[row * self.columns + column];
+            let (y0, y1) = (ray.at(entry).y, ray.at(exit).y);
+            if y0.min(y1) <= heights.max
+                && heights.min <= y0.max(y1)
+                && let Some((hit, indices)) = self.hit_cell(column, row, ray, ray_t)
+            {
+                return Some(self.record(ray, hit, indices));
+            }
+
+            if exit >= span.max {
+                return None;
+            }
+            if next_x < next_z {
+                column = column.checked_add_signed(step_x)?;
+                next_x += delta_x;
+            } else {
+                row = row.checked_add_signed(step_z)?;
+                next_z += delta_z;
+            }
+            if column >= self.columns || row >= self.rows {
+                return None;
+            }
+            entry = exit;
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{color::Color, materials::Lambertian, vec3};
+
+    // Gentle hills, with a height given by a formula, to check hits against.
+    fn hills(x: f64, z: f64) -> f64 {
+        0.5 + 0.3 * (x * 1.3).sin() * (z * 0.7).cos()
+    }
+
+    #[test]
+    fn rays_land_on_the_surface() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let terrain = Heightfield::from_fn(
+            Point3::new(-5., 0., -5.),
+            10.,
+            10.,
+            200,
+            200,
+            hills,
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        );
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        // From above, at a slant, and from a low angle across the terrain.
+        for (origin, direction) in [
+            (Point3::new(1., 10., 2.), Direction::new(0.1, -1., -0.2)),
+            (Point3::new(-3., 3., 7.), Direction::new(0.3, -0.4, -1.)),
+            (Point3::new(8., 1., 0.5), Direction::new(-1., -0.05, 0.02)),
+        ] {
+            let ray = Ray::new(origin, direction);
+            let hit = terrain.hit(&ray, &ray_t, &mut rng).unwrap();
+            let expected = hills(hit.point.x, hit.point.z);
+            assert!((hit.point.y - expected).abs() < 1e-3, "{:?}", hit.point);
+            assert!(hit.front_face);
+
+            // Nothing nearer: the ray stays above the surface all the way to the hit.
+            for step in 1..1000 {
+                let point = ray.at(hit.t * step as f64 / 1000.);
+                let inside = terrain.bbox.contains(&point);
+                assert!(!inside || point.y > hills(point.x, point.z) - 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn smooth_normals_and_misses() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        // A plane sloping up towards +x at 45 degrees.
+        let ramp = Heightfield::from_fn(
+            Point3::new(0., 0., 0.),
+            4.,
+            4.,
+            8,
+            8,
+            |x, _| x,
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        );
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        let ray = Ray::new(Point3::new(1.3, 10., 2.7), Direction::new(0., -1., 0.));
+        let hit = ramp.hit(&ray, &ray_t, &mut rng).unwrap();
+        assert!((hit.point.y - 1.3).abs() < 1e-9);
+        assert!(vec3::approx_eq(
+            hit.outward_normal(),
+            Direction::new(-1., 1., 0.).normalize(),
+            1e-9
+        ));
+
+        // Over the ramp without touching it, and off the edge.
+        let ray = Ray::new(Point3::new(-1., 5., 2.), Direction::new(1., 0., 0.));
+        assert!(ramp.hit(&ray, &ray_t, &mut rng).is_none());
+        let ray = Ray::new(Point3::new(5., 10., 2.), Direction::new(0., -1., 0.));
+        assert!(ramp.hit(&ray, &ray_t, &mut rng).is_none());
+    }
+}
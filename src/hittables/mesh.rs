@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable, HittableList, bvh::Bvh, triangle},
+    interval::Interval,
+    materials::Material,
+    ray::Ray,
+    vec3::{Direction, Point3},
+};
+
+// Vertex and index buffers shared by all the triangles of a mesh.
+pub struct MeshData {
+    pub positions: Vec<Point3>,
+    pub normals: Option<Vec<Direction>>, // One per position, for smooth shading.
+    pub texcoords: Option<Vec<[f64; 2]>>, // One per position, for mapping textures.
+    pub indices: Vec<[usize; 3]>,        // Each triple indexes the vertices of one triangle.
+}
+
+// Many triangles sharing one set of buffers and one material. The triangles are stored in their own bounding volume hierarchy, so a mesh can be added to a `HittableList` like any other shape.
+pub struct TriangleMesh {
+    data: Arc<MeshData>,
+    bvh: Bvh,
+}
+
+// A single triangle of a mesh, referring to its vertices by index.
+struct MeshTriangle {
+    mesh: Arc<Shared>,
+    index: usize,
+}
+
+struct Shared {
+    data: Arc<MeshData>,
+    material: Arc<dyn Material>,
+}
+
+impl TriangleMesh {
+    pub fn new(data: MeshData, material: Arc<dyn Material>) -> Self {
+        let vertex_count = data.positions.len();
+        assert!(
+            data.indices.iter().flatten().all(|&i| i < vertex_count),
+            "Vertex index out of bounds"
+        );
+        if let Some(normals) = &data.normals {
+            assert!(
+                normals.len() == vertex_count,
+                "Number of normals doesn't match number of positions"
+            );
+        }
+        if let Some(texcoords) = &data.texcoords {
+            assert!(
+                texcoords.len() == vertex_count,
+                "Number of texture coordinates doesn't match number of positions"
+            );
+        }
+
+        let data = Arc::new(data);
+        let shared = Arc::new(Shared {
+            data: data.clone(),
+            material,
+        });
+
+        let mut triangles = HittableList::new();
+        for index in 0..data.indices.len() {
+            triangles.add(Arc::new(MeshTriangle {
+                mesh: shared.clone(),
+                index,
+            }));
+        }
+
+        Self {
+            data,
+            bvh: Bvh::new(triangles),
+        }
+    }
+
+    pub fn data(&self) -> &MeshData {
+        &self.data
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.data.indices.len()
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Option<HitRecord> {
+        self.bvh.hit(ray, ray_t, rng)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+}
+
+impl MeshTriangle {
+    fn vertices(&self) -> [Point3; 3] {
+        self.mesh.data.indices[self.index].map(|i| self.mesh.data.positions[i])
+    }
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Option<HitRecord> {
+        let vertices = self.vertices();
+        let indices = self.mesh.data.indices[self.index];
+        let hit = triangle::intersect(&vertices, ray, ray_t)?;
+        let outward_normal = match &self.mesh.data.normals {
+            Some(normals) => {
+                let normals = indices.map(|i| normals[i]);
+                triangle::interpolate(&normals, hit.beta, hit.gamma)
+            }
+            None => hit.geometric_normal,
+        };
+
+        // Interpolate the mesh's texture coordinates if it has them, otherwise fall back to the barycentric coordinates, as for a lone triangle.
+        let alpha = 1. - hit.beta - hit.gamma;
+        let ((u, v), tangent) = match &self.mesh.data.texcoords {
+            Some(texcoords) => {
+                let texcoords = indices.map(|i| texcoords[i]);
+                let [[u_a, v_a], [u_b, v_b], [u_c, v_c]] = texcoords;
+                let uv = (
+                    alpha * u_a + hit.beta * u_b + hit.gamma * u_c,
+                    alpha * v_a + hit.beta * v_b + hit.gamma * v_c,
+                );
+                let tangent = triangle::texture_tangent(&vertices, &texcoords)
+                    .unwrap_or(vertices[1] - vertices[0]);
+                (uv, tangent)
+            }
+            None => ((hit.beta, hit.gamma), vertices[1] - vertices[0]),
+        };
+
+        Some(
+            HitRecord::new(
+                ray.at(hit.t),
+                outward_normal,
+                hit.t,
+                self.mesh.material.clone(),
+                ray,
+            )
+            .with_uv(u, v)
+            .with_tangent(tangent),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        triangle::bounding_box(&self.vertices())
+    }
+}
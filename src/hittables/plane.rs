@@ -3,11 +3,12 @@ use std::sync::Arc;
 use rand::rngs::SmallRng;
 
 use crate::{
+    aabb::Aabb,
     hittables::{HitRecord, Hittable},
     interval::Interval,
     materials::Material,
     ray::Ray,
-    vec3::{Direction, Point3},
+    vec3::{self, Direction, Point3},
 };
 
 pub struct Plane {
@@ -15,6 +16,7 @@ pub struct Plane {
     pub normal: Direction,
     pub material: Arc<dyn Material>,
     pub offset: f64,
+    axes: [Direction; 2], // Orthonormal, in the plane, so that surface coordinates are distances along them from `point`.
 }
 
 impl Plane {
@@ -27,6 +29,7 @@ impl Plane {
             normal,
             material,
             offset,
+            axes: vec3::orthonormal_basis_2d(&normal),
         }
     }
 
@@ -56,6 +59,7 @@ impl Plane {
             normal,
             material,
             offset,
+            axes: [u, normal.cross(&u)],
         }
     }
 }
@@ -78,12 +82,19 @@ impl Hittable for Plane {
         let point = ray.at(t);
         let outward_normal = self.normal;
 
-        Some(HitRecord::new(
-            point,
-            outward_normal,
-            t,
-            self.material.clone(),
-            ray,
-        ))
+        // The plane is unbounded, so its surface coordinates are too: they're measured in world units, and a texture that should repeat has to wrap them itself.
+        let offset = point - self.point;
+        let [axis_u, axis_v] = &self.axes;
+        let (u, v) = (axis_u.dot(&offset), axis_v.dot(&offset));
+
+        Some(
+            HitRecord::new(point, outward_normal, t, self.material.clone(), ray)
+                .with_uv(u, v)
+                .with_tangent(*axis_u),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::UNIVERSE
     }
 }
@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
-use rand::rngs::SmallRng;
+use rand::{Rng, rngs::SmallRng};
 
 use crate::{
+    aabb::Aabb,
     hittables::{HitRecord, Hittable},
     interval::Interval,
     materials::Material,
@@ -76,12 +77,24 @@ impl Hittable for Quad {
             return None;
         }
 
-        Some(HitRecord::new(
-            intersection,
-            self.normal,
-            t,
-            self.material.clone(),
-            ray,
-        ))
+        Some(
+            HitRecord::new(intersection, self.normal, t, self.material.clone(), ray)
+                .with_uv(alpha, beta)
+                .with_tangent(self.u),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let diagonal_1 = Aabb::from_points(self.point, self.point + self.u + self.v);
+        let diagonal_2 = Aabb::from_points(self.point + self.u, self.point + self.v);
+        Aabb::surrounding(&diagonal_1, &diagonal_2)
+    }
+
+    fn area(&self) -> f64 {
+        self.w.length()
+    }
+
+    fn sample_surface(&self, _time: f64, rng: &mut SmallRng) -> Option<Point3> {
+        Some(self.point + rng.random::<f64>() * self.u + rng.random::<f64>() * self.v)
     }
 }
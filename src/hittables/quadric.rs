@@ -0,0 +1,306 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable},
+    interval::Interval,
+    materials::Material,
+    ray::Ray,
+    vec3::{Direction, Point3, matrix::Mat3},
+};
+
+// A quadric surface: the points p where F(p) = p·Ap + 2b·p + c = 0, for a symmetric matrix A. The inside is where F(p) < 0, and the outward normal is the gradient of F. Spheres, ellipsoids, paraboloids, hyperboloids, cylinders and cones are all quadrics. Many of them are infinite, so the surface can be clipped to a box. The convenience constructors below are aligned with the world axes; to turn one, wrap it in a `Transformed`.
+pub struct Quadric {
+    a: Mat3,
+    b: Direction,
+    c: f64,
+    material: Arc<dyn Material>,
+    clip: Aabb, // Only the part of the surface in here is kept.
+    bbox: Aabb,
+}
+
+impl Quadric {
+    // Only the symmetric part of `a` matters, so that's what's kept.
+    pub fn new(a: Mat3, b: Direction, c: f64, material: Arc<dyn Material>) -> Self {
+        let transpose = a.transpose();
+        let a = Mat3::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| 0.5 * (a.rows[i][j] + transpose.rows[i][j]))
+        }));
+        Self {
+            a,
+            b,
+            c,
+            material,
+            clip: Aabb::UNIVERSE,
+            bbox: Aabb::UNIVERSE,
+        }
+    }
+
+    // The quadric (p - origin)·A(p - origin) + 2b·(p - origin) + c = 0, i.e. one given relative to `origin`, moved there.
+    fn moved(a: Mat3, b: Direction, c: f64, origin: Point3, material: Arc<dyn Material>) -> Self {
+        let o = origin - Point3::new(0., 0., 0.);
+        let a_o = a * o;
+        Self::new(a, b - a_o, o.dot(&a_o) - 2. * b.dot(&o) + c, material)
+    }
+
+    // Keep only the part of the surface inside `region`.
+    pub fn with_clip(mut self, region: Aabb) -> Self {
+        self.clip = Aabb::overlap(&self.clip, &region);
+        self.bbox = Aabb::overlap(&self.bbox, &region);
+        self
+    }
+
+    // A closed surface, like a sphere stretched by a different amount along each axis.
+    pub fn ellipsoid(center: Point3, radii: Direction, material: Arc<dyn Material>) -> Self {
+        assert!(
+            radii.into_iter().all(|radius| 1e-8 < radius),
+            "Radius is too small"
+        );
+        let scale = |radius: f64| 1. / (radius * radius);
+        let mut ellipsoid = Self::moved(
+            Mat3::scaling(scale(radii.x), scale(radii.y), scale(radii.z)),
+            Direction::new(0., 0., 0.),
+            -1.,
+            center,
+            material,
+        );
+        ellipsoid.bbox = Aabb::around(center, radii);
+        ellipsoid
+    }
+
+    // A dish, opening upwards (+y) from `vertex`, `depth` deep. Rays coming straight down into it are reflected through the focus, `focal_length` above the vertex, which makes it the shape for a telescope mirror or a searchlight.
+    pub fn paraboloid(
+        vertex: Point3,
+        focal_length: f64,
+        depth: f64,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        assert!(1e-8 < focal_length, "Focal length is too small");
+        assert!(1e-8 < depth, "Depth is too small");
+        // x² + z² = 4 f y.
+        let paraboloid = Self::moved(
+            Mat3::scaling(1., 0., 1.),
+            Direction::new(0., -2. * focal_length, 0.),
+            0.,
+            vertex,
+            material,
+        );
+        let radius = (4. * focal_length * depth).sqrt();
+        let mut paraboloid = paraboloid.with_clip(Aabb::new(
+            Interval::FULL,
+            Interval::new(vertex.y, vertex.y + depth),
+            Interval::FULL,
+        ));
+        paraboloid.bbox = Aabb::from_points(
+            vertex + Direction::new(-radius, 0., -radius),
+            vertex + Direction::new(radius, depth, radius),
+        );
+        paraboloid
+    }
+
+    // A hyperboloid of one sheet, like a cooling tower: a tube around the y axis that's narrowest, with radius `waist_radius`, at `center`, and widens to `end_radius` at `half_height` above and below it.
+    pub fn hyperboloid(
+        center: Point3,
+        waist_radius: f64,
+        end_radius: f64,
+        half_height: f64,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        assert!(1e-8 < waist_radius, "Radius is too small");
+        assert!(1e-8 < half_height, "Height is too small");
+        assert!(
+            waist_radius < end_radius,
+            "End radius must be greater than waist radius"
+        );
+        // (x² + z²)/w² - y²/k² = 1, where k is chosen to make the radius `end_radius` at the ends.
+        let waist_squared = waist_radius * waist_radius;
+        let k_squared = half_height * half_height / (end_radius * end_radius / waist_squared - 1.);
+        let hyperboloid = Self::moved(
+            Mat3::scaling(1. / waist_squared, -1. / k_squared, 1. / waist_squared),
+            Direction::new(0., 0., 0.),
+            -1.,
+            center,
+            material,
+        );
+        let mut hyperboloid = hyperboloid.with_clip(Aabb::new(
+            Interval::FULL,
+            Interval::new(center.y - half_height, center.y + half_height),
+            Interval::FULL,
+        ));
+        hyperboloid.bbox =
+            Aabb::around(center, Direction::new(end_radius, half_height, end_radius));
+        hyperboloid
+    }
+
+    // Half the gradient of F.
+    fn gradient(&self, point: &Point3) -> Direction {
+        self.a * (*point - Point3::new(0., 0., 0.)) + self.b
+    }
+
+    // Where the ray's line meets the surface, nearer first.
+    fn roots(&self, ray: &Ray) -> Vec<f64> {
+        // F(origin + t direction) = a t² + 2h t + c.
+        let origin = ray.origin - Point3::new(0., 0., 0.);
+        let a_d = self.a * ray.direction;
+        let a = ray.direction.dot(&a_d);
+        let h = origin.dot(&a_d) + self.b.dot(&ray.direction);
+        let c = origin.dot(&(self.a * origin)) + 2. * self.b.dot(&origin) + self.c;
+
+        // Along some directions, e.g. parallel to the axis of a paraboloid, the equation is linear and there's only one root.
+        if a.abs() < 1e-12 {
+            return if h.abs() < 1e-12 {
+                Vec::new()
+            } else {
+                vec![-c / (2. * h)]
+            };
+        }
+
+        let discriminant = h * h - a * c;
+        if discriminant < 0. {
+            return Vec::new();
+        }
+        let sqrt_d = discriminant.sqrt();
+        let (t0, t1) = ((-h - sqrt_d) / a, (-h + sqrt_d) / a);
+        vec![t0.min(t1), t0.max(t1)]
+    }
+
+    fn record(&self, ray: &Ray, t: f64) -> Option<HitRecord> {
+        let point = ray.at(t);
+        if !self.clip.contains(&point) {
+            return None;
+        }
+        let gradient = self.gradient(&point);
+        if gradient.near_zero() {
+            return None; // A singular point, such as the tip of a cone, which has no normal.
+        }
+        let outward_normal = gradient.normalize();
+
+        // u is the angle around the vertical line through the center of the box, v the height as a fraction of the box's, if they're finite.
+        let center = self.bbox.centroid();
+        let radial = Direction::new(point.x - center.x, 0., point.z - center.z);
+        let (u, v) = if self.bbox.is_bounded() {
+            let angle = (-radial.z).atan2(radial.x) + PI;
+            (
+                angle / (2. * PI),
+                (point.y - self.bbox.y.min) / self.bbox.y.size(),
+            )
+        } else {
+            (0., 0.)
+        };
+
+        Some(
+            HitRecord::new(point, outward_normal, t, self.material.clone(), ray)
+                .with_uv(u, v)
+                .with_tangent(Direction::new(radial.z, 0., -radial.x)),
+        )
+    }
+}
+
+impl Hittable for Quadric {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Option<HitRecord> {
+        self.roots(ray)
+            .into_iter()
+            .filter(|&t| ray_t.surrounds(t))
+            .find_map(|t| self.record(ray, t))
+    }
+
+    fn all_hits(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Vec<HitRecord> {
+        self.roots(ray)
+            .into_iter()
+            .filter(|&t| ray_t.surrounds(t))
+            .filter_map(|t| self.record(ray, t))
+            .collect()
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{color::Color, materials::Lambertian, vec3};
+
+    #[test]
+    fn ellipsoid_normals_point_out() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let ellipsoid = Quadric::ellipsoid(
+            Point3::new(1., 2., 3.),
+            Direction::new(2., 1., 1.),
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        );
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        let ray = Ray::new(Point3::new(10., 2., 3.), Direction::new(-1., 0., 0.));
+        let hits = ellipsoid.all_hits(&ray, &ray_t, &mut rng);
+        assert_eq!(hits.len(), 2);
+        assert!(vec3::approx_eq(
+            hits[0].point,
+            Point3::new(3., 2., 3.),
+            1e-9
+        ));
+        assert!(hits[0].front_face && !hits[1].front_face);
+        assert!(vec3::approx_eq(
+            hits[1].outward_normal(),
+            Direction::new(-1., 0., 0.),
+            1e-9
+        ));
+
+        // The same as a sphere stretched by a transformation.
+        let ray = Ray::new(Point3::new(2., 10., 3.5), Direction::new(0., -1., 0.));
+        let hit = ellipsoid.hit(&ray, &ray_t, &mut rng).unwrap();
+        let expected = Direction::new(0.25, 0.5f64.sqrt(), 0.5).normalize();
+        assert!(vec3::approx_eq(hit.outward_normal(), expected, 1e-9));
+    }
+
+    #[test]
+    fn paraboloid_reflects_through_the_focus() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let vertex = Point3::new(0., -1., 0.);
+        let dish = Quadric::paraboloid(
+            vertex,
+            0.5,
+            2.,
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        );
+        let focus = vertex + Direction::new(0., 0.5, 0.);
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        for (x, z) in [(0.3, 0.), (-1., 0.5), (0.2, -1.7)] {
+            let ray = Ray::new(Point3::new(x, 5., z), Direction::new(0., -1., 0.));
+            let hit = dish.hit(&ray, &ray_t, &mut rng).unwrap();
+            let reflected = ray.direction.reflect(&hit.normal);
+            let to_focus = (focus - hit.point).normalize();
+            assert!(vec3::approx_eq(reflected, to_focus, 1e-9));
+        }
+
+        // Past the rim, the infinite paraboloid is clipped away.
+        let ray = Ray::new(Point3::new(2.5, 5., 0.), Direction::new(0., -1., 0.));
+        assert!(dish.hit(&ray, &ray_t, &mut rng).is_none());
+    }
+
+    #[test]
+    fn hyperboloid_waist_and_ends() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let tower = Quadric::hyperboloid(
+            Point3::new(0., 0., 0.),
+            1.,
+            2.,
+            3.,
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        );
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        for (height, radius) in [(0., 1.), (3. - 1e-9, 2.)] {
+            let ray = Ray::new(Point3::new(10., height, 0.), Direction::new(-1., 0., 0.));
+            let hit = tower.hit(&ray, &ray_t, &mut rng).unwrap();
+            assert!((hit.point.x - radius).abs() < 1e-6);
+        }
+        let ray = Ray::new(Point3::new(10., 3.1, 0.), Direction::new(-1., 0., 0.));
+        assert!(tower.hit(&ray, &ray_t, &mut rng).is_none());
+    }
+}
@@ -0,0 +1,176 @@
+use std::sync::Arc;
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable},
+    interval::Interval,
+    materials::Material,
+    ray::Ray,
+    sdf::Sdf,
+    vec3::{Direction, Point3},
+};
+
+// How close to the surface counts as on it.
+const SURFACE_DISTANCE: f64 = 1e-5;
+// Give up on rays that creep along close to the surface without reaching it.
+const MAX_STEPS: usize = 512;
+
+// A shape given by a signed distance function, found by sphere tracing: stepping along the ray, each time by the distance to the nearest surface, until the ray is close enough to be on it. Only the part of the shape within `bbox` is traced, so the box must contain everything you want to see.
+pub struct SdfHittable {
+    sdf: Arc<dyn Sdf>,
+    bbox: Aabb,
+    material: Arc<dyn Material>,
+    step_scale: f64,
+}
+
+impl SdfHittable {
+    pub fn new(sdf: Arc<dyn Sdf>, bbox: Aabb, material: Arc<dyn Material>) -> Self {
+        assert!(bbox.is_bounded(), "Bounding box must be finite");
+        Self {
+            sdf,
+            bbox,
+            material,
+            step_scale: 1.,
+        }
+    }
+
+    // Take only this fraction of each step. For distance estimates that can overshoot, such as a `Twist` or `Displace`, a fraction below 1 stops the ray passing through thin parts of the surface, at the cost of more steps.
+    pub fn with_step_scale(mut self, step_scale: f64) -> Self {
+        assert!(
+            0. < step_scale && step_scale <= 1.,
+            "Step scale must be in (0, 1]"
+        );
+        self.step_scale = step_scale;
+        self
+    }
+
+    // The direction in which the distance increases fastest, by central differences.
+    fn outward_normal(&self, point: &Point3) -> Direction {
+        let h = SURFACE_DISTANCE;
+        let difference = |offset: Direction| {
+            self.sdf.distance(&(*point + offset)) - self.sdf.distance(&(*point - offset))
+        };
+        let gradient = Direction::new(
+            difference(Direction::new(h, 0., 0.)),
+            difference(Direction::new(0., h, 0.)),
+            difference(Direction::new(0., 0., h)),
+        );
+        gradient.normalize()
+    }
+}
+
+impl Hittable for SdfHittable {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Option<HitRecord> {
+        let span = self.bbox.hit_interval(ray, ray_t)?;
+
+        // Look for the surface from whichever side the ray starts on: a ray that has just been refracted into the shape is looking for the way out. A ray starting on the surface, e.g. having just been reflected off it, first has to get clear of it to tell which side it's on.
+        let mut t = span.min;
+        let mut side = 0.;
+        for _ in 0..MAX_STEPS {
+            if t > span.max {
+                return None;
+            }
+            let distance = self.sdf.distance(&ray.at(t));
+            if side == 0. {
+                if distance.abs() < SURFACE_DISTANCE {
+                    t += SURFACE_DISTANCE;
+                    continue;
+                }
+                side = distance.signum();
+            }
+
+            let distance = side * distance;
+            if distance < SURFACE_DISTANCE {
+                let point = ray.at(t);
+                let outward_normal = self.outward_normal(&point);
+                if !outward_normal.x.is_finite() {
+                    return None;
+                }
+                return Some(HitRecord::new(
+                    point,
+                    outward_normal,
+                    t,
+                    self.material.clone(),
+                    ray,
+                ));
+            }
+            t += self.step_scale * distance;
+        }
+        None
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{
+        color::Color,
+        hittables::{HittableList, csg::Operation},
+        materials::Lambertian,
+        sdf::{Combination, Sphere},
+        vec3,
+    };
+
+    fn ball() -> SdfHittable {
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let sphere = Arc::new(Sphere::new(Point3::new(0., 0., 0.), 1.));
+        let bbox = Aabb::around(Point3::new(0., 0., 0.), Direction::new(1., 1., 1.));
+        SdfHittable::new(sphere, bbox, material)
+    }
+
+    #[test]
+    fn traced_sphere_matches_the_real_thing() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut world = HittableList::new();
+        world.add(Arc::new(ball()));
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        let ray = Ray::new(Point3::new(0.6, 0., 5.), Direction::new(0., 0., -1.));
+        let hit = world.hit(&ray, &ray_t, &mut rng).unwrap();
+        let expected = Point3::new(0.6, 0., 0.8);
+        assert!(vec3::approx_eq(hit.point, expected, 1e-4));
+        assert!(vec3::approx_eq(
+            hit.outward_normal(),
+            Direction::new(0.6, 0., 0.8),
+            1e-4
+        ));
+        assert!(hit.front_face);
+
+        // Carrying on from there, the ray finds the far side, from inside.
+        let ray = Ray::new(hit.point, ray.direction);
+        let hit = world.hit(&ray, &ray_t, &mut rng).unwrap();
+        assert!(vec3::approx_eq(hit.point, Point3::new(0.6, 0., -0.8), 1e-4));
+        assert!(!hit.front_face);
+
+        let ray = Ray::new(Point3::new(1.1, 0., 5.), Direction::new(0., 0., -1.));
+        assert!(world.hit(&ray, &ray_t, &mut rng).is_none());
+    }
+
+    #[test]
+    fn smooth_union_fills_the_gap() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        // Two spheres, just apart.
+        let a = Arc::new(Sphere::new(Point3::new(-1.05, 0., 0.), 1.));
+        let b = Arc::new(Sphere::new(Point3::new(1.05, 0., 0.), 1.));
+        let bbox = Aabb::around(Point3::new(0., 0., 0.), Direction::new(2.5, 1.5, 1.5));
+        let ray = Ray::new(Point3::new(0., 5., 0.), Direction::new(0., -1., 0.));
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        let sharp = Combination::new(a.clone(), b.clone(), Operation::Union);
+        let sharp = SdfHittable::new(Arc::new(sharp), bbox, material.clone());
+        assert!(sharp.hit(&ray, &ray_t, &mut rng).is_none());
+
+        let smooth = Combination::smooth(a, b, Operation::Union, 0.5);
+        let smooth = SdfHittable::new(Arc::new(smooth), bbox, material);
+        assert!(smooth.hit(&ray, &ray_t, &mut rng).is_some());
+    }
+}
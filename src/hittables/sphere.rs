@@ -1,19 +1,22 @@
-use std::sync::Arc;
+use std::{f64::consts::PI, sync::Arc};
 
-use rand::rngs::SmallRng;
+use rand::{Rng, rngs::SmallRng};
 
 use crate::{
+    aabb::Aabb,
     hittables::{HitRecord, Hittable},
     interval::Interval,
     materials::Material,
+    motion::Motion,
     ray::Ray,
-    vec3::Point3,
+    vec3::{self, Direction, Point3},
 };
 
 pub struct Sphere {
     center: Point3,
     radius: f64,
     material: Arc<dyn Material>,
+    motion: Motion,
 }
 
 impl Sphere {
@@ -24,37 +27,145 @@ impl Sphere {
             center,
             radius,
             material,
+            motion: Motion::none(),
         }
     }
+
+    // Move the sphere while the shutter is open, which blurs it along its path.
+    pub fn with_motion(mut self, motion: Motion) -> Self {
+        self.motion = motion;
+        self
+    }
+
+    fn center_at(&self, time: f64) -> Point3 {
+        self.center + self.motion.offset(time)
+    }
+
+    // Map a point on the unit sphere to longitude u, from 0 at -x round through +z to 1, and latitude v, from 0 at the south pole (-y) to 1 at the north pole.
+    fn uv(point: &Direction) -> (f64, f64) {
+        let theta = (-point.y).clamp(-1., 1.).acos();
+        let phi = (-point.z).atan2(point.x) + PI;
+        (phi / (2. * PI), theta / PI)
+    }
 }
 
-impl Hittable for Sphere {
-    fn hit(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Option<HitRecord> {
-        let origin_to_center = self.center - ray.origin;
+impl Sphere {
+    // The cosine of the angle between the line from `origin` to the center and the edge of the sphere as seen from there, or `None` if `origin` is inside.
+    fn cos_theta_max(&self, origin: &Point3, time: f64) -> Option<f64> {
+        let distance_squared = (self.center_at(time) - *origin).length_squared();
+        let ratio = self.radius * self.radius / distance_squared;
+        (ratio < 1.).then(|| (1. - ratio).sqrt())
+    }
+
+    // Where the ray's line meets the sphere, if at all, nearer first.
+    fn roots(&self, ray: &Ray) -> Option<[f64; 2]> {
+        let origin_to_center = self.center_at(ray.time) - ray.origin;
         let a = ray.direction.dot(&ray.direction);
         let h = ray.direction.dot(&origin_to_center);
-        let c = origin_to_center.dot(&origin_to_center) - self.radius * self.radius;
-        let discriminant = h * h - a * c;
+        // h² - ac, rearranged to avoid subtracting huge, nearly equal numbers when the ray starts far away: a times the squared radius less the squared distance from the center to the nearest point on the line.
+        let to_nearest = origin_to_center - (h / a) * ray.direction;
+        let discriminant = a * (self.radius * self.radius - to_nearest.dot(&to_nearest));
 
         if discriminant < 0.0 {
             return None;
         }
 
         let sqrt_d = discriminant.sqrt();
+        Some([(h - sqrt_d) / a, (h + sqrt_d) / a])
+    }
+
+    fn record(&self, ray: &Ray, t: f64) -> HitRecord {
+        let point = ray.at(t);
+        let outward_normal = (point - self.center_at(ray.time)) / self.radius;
+        let (u, v) = Self::uv(&outward_normal);
+        // Moving east, in the direction of increasing longitude.
+        let tangent = Direction::new(outward_normal.z, 0., -outward_normal.x);
+        HitRecord::new(point, outward_normal, t, self.material.clone(), ray)
+            .with_uv(u, v)
+            .with_tangent(tangent)
+    }
+}
 
+impl Hittable for Sphere {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Option<HitRecord> {
         // Find the nearest root that lies in the acceptable range.
-        let mut t = (h - sqrt_d) / a;
-        if !ray_t.surrounds(t) {
-            t = (h + sqrt_d) / a;
-            if !ray_t.surrounds(t) {
-                return None;
-            }
+        let t = self.roots(ray)?.into_iter().find(|&t| ray_t.surrounds(t))?;
+        Some(self.record(ray, t))
+    }
+
+    fn all_hits(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Vec<HitRecord> {
+        self.roots(ray)
+            .into_iter()
+            .flatten()
+            .filter(|&t| ray_t.surrounds(t))
+            .map(|t| self.record(ray, t))
+            .collect()
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let bbox = Aabb::around(
+            self.center,
+            Direction::new(self.radius, self.radius, self.radius),
+        );
+        self.motion.sweep(&bbox)
+    }
+
+    fn area(&self) -> f64 {
+        4. * PI * self.radius * self.radius
+    }
+
+    fn sample_surface(&self, time: f64, rng: &mut SmallRng) -> Option<Point3> {
+        Some(self.center_at(time) + self.radius * Direction::random_unit(rng))
+    }
+
+    // As a light, it's better to choose directions than points: uniformly over the cone of directions in which it's seen from `origin`. From inside, it's all around, so all directions are equally likely.
+    fn pdf_value(
+        &self,
+        origin: &Point3,
+        direction: &Direction,
+        time: f64,
+        _rng: &mut SmallRng,
+    ) -> f64 {
+        let Some(cos_theta_max) = self.cos_theta_max(origin, time) else {
+            return 1. / (4. * PI);
+        };
+        let to_center = (self.center_at(time) - *origin).normalize();
+        if direction.normalize().dot(&to_center) < cos_theta_max {
+            return 0.;
         }
+        1. / (2. * PI * (1. - cos_theta_max))
+    }
 
-        let point = ray.at(t);
-        let outward_normal = (point - self.center) / self.radius;
-        let record = HitRecord::new(point, outward_normal, t, self.material.clone(), ray);
+    fn random_direction(&self, origin: &Point3, time: f64, rng: &mut SmallRng) -> Direction {
+        let Some(cos_theta_max) = self.cos_theta_max(origin, time) else {
+            return Direction::random_unit(rng);
+        };
+        let to_center = (self.center_at(time) - *origin).normalize();
+        let [u, v] = vec3::orthonormal_basis_2d(&to_center);
+        let cos_theta = 1. + rng.random::<f64>() * (cos_theta_max - 1.);
+        let sin_theta = (1. - cos_theta * cos_theta).max(0.).sqrt();
+        let phi = rng.random_range(0.0..2. * PI);
+        sin_theta * (phi.cos() * u + phi.sin() * v) + cos_theta * to_center
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{color::Color, materials::Lambertian};
 
-        Some(record)
+    #[test]
+    fn rays_from_far_away_still_hit_the_surface() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let sphere = Sphere::new(Point3::new(0., 1., 0.), 1., material);
+        let origin = Point3::new(-2.3e7, 1., 9.5e7);
+        let ray = Ray::new(origin, Point3::new(0., 1.5, 0.) - origin);
+        let record = sphere
+            .hit(&ray, &Interval::new(0.001, f64::INFINITY), &mut rng)
+            .unwrap();
+        assert!((record.outward_normal().length() - 1.).abs() < 1e-6);
     }
 }
@@ -0,0 +1,351 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable},
+    interval::Interval,
+    materials::Material,
+    ray::Ray,
+    vec3::{self, Direction, Point3},
+};
+
+// A ring doughnut: the surface swept out by a circle of radius `minor_radius` whose center goes round a circle of radius `major_radius` about `axis`.
+pub struct Torus {
+    center: Point3,
+    axis: Direction,
+    major_radius: f64,
+    minor_radius: f64,
+    material: Arc<dyn Material>,
+    reference: [Direction; 2], // Perpendicular to the axis: angles around it are measured from the first towards the second.
+}
+
+impl Torus {
+    pub fn new(
+        center: Point3,
+        axis: Direction,
+        major_radius: f64,
+        minor_radius: f64,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        assert!(!axis.near_zero(), "Axis vector is too small");
+        assert!(1e-8 < minor_radius, "Minor radius is too small");
+        assert!(
+            minor_radius < major_radius,
+            "Minor radius must be less than major radius"
+        );
+
+        let axis = axis.normalize();
+        Self {
+            center,
+            axis,
+            major_radius,
+            minor_radius,
+            material,
+            reference: vec3::orthonormal_basis_2d(&axis),
+        }
+    }
+
+    // Where the ray's line meets the torus, nearest first.
+    fn roots(&self, ray: &Ray) -> Vec<f64> {
+        // Measure from the point on the line nearest the center. Starting far away, the coefficients of the quartic would be huge and nearly cancel, losing most of the precision of the roots.
+        let shift = (self.center - ray.origin).dot(&ray.direction);
+        let origin = ray.at(shift) - self.center;
+        let (big, small) = (self.major_radius, self.minor_radius);
+        if origin.length() > big + small {
+            return Vec::new();
+        }
+
+        // Points p on the torus satisfy (|p|² + R² - r²)² = 4R²(|p|² - (p·axis)²). Substituting p = origin + t direction, where origin is perpendicular to the unit direction, gives a quartic in t.
+        let origin_along_axis = origin.dot(&self.axis);
+        let direction_along_axis = ray.direction.dot(&self.axis);
+        let k = origin.length_squared() + big * big - small * small;
+        let four_big_squared = 4. * big * big;
+        let mut roots = solve_quartic(
+            0.,
+            2. * k - four_big_squared * (1. - direction_along_axis * direction_along_axis),
+            four_big_squared * 2. * origin_along_axis * direction_along_axis,
+            k * k
+                - four_big_squared
+                    * (origin.length_squared() - origin_along_axis * origin_along_axis),
+        );
+        for root in &mut roots {
+            *root += shift;
+        }
+        roots
+    }
+
+    fn record(&self, ray: &Ray, t: f64) -> HitRecord {
+        let point = ray.at(t);
+        let local = point - self.center;
+        let height = local.dot(&self.axis);
+        let radial = local - height * self.axis;
+        let distance = radial.length();
+
+        // The nearest point on the circle through the middle of the tube. Exactly on the axis, every point of the circle is equally near, but the torus doesn't reach the axis.
+        let radial = if distance > 1e-12 {
+            radial / distance
+        } else {
+            self.reference[0]
+        };
+        let ring = self.center + self.major_radius * radial;
+        let outward_normal = (point - ring).normalize();
+
+        // u is the angle around the axis, v the angle around the tube, starting from the outer equator and going over the top, both as fractions of a full turn.
+        let [reference_u, reference_v] = &self.reference;
+        let around_axis = radial.dot(reference_v).atan2(radial.dot(reference_u));
+        let around_tube = height.atan2(distance - self.major_radius);
+        let u = around_axis.rem_euclid(2. * PI) / (2. * PI);
+        let v = around_tube.rem_euclid(2. * PI) / (2. * PI);
+
+        HitRecord::new(point, outward_normal, t, self.material.clone(), ray)
+            .with_uv(u, v)
+            .with_tangent(self.axis.cross(&radial))
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Option<HitRecord> {
+        let t = self.roots(ray).into_iter().find(|&t| ray_t.surrounds(t))?;
+        Some(self.record(ray, t))
+    }
+
+    fn all_hits(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Vec<HitRecord> {
+        self.roots(ray)
+            .into_iter()
+            .filter(|&t| ray_t.surrounds(t))
+            .map(|t| self.record(ray, t))
+            .collect()
+    }
+
+    // Along each world axis, the circle through the middle of the tube reaches R times the sine of the angle between that axis and the torus's, and the tube r further.
+    fn bounding_box(&self) -> Aabb {
+        let extent = |a: f64| self.major_radius * (1. - a * a).max(0.).sqrt() + self.minor_radius;
+        Aabb::around(
+            self.center,
+            Direction::new(
+                extent(self.axis.x),
+                extent(self.axis.y),
+                extent(self.axis.z),
+            ),
+        )
+    }
+}
+
+// Real roots, in increasing order, of x⁴ + b x³ + c x² + d x + e, by Ferrari's method. Each is then polished with a few steps of Newton's method, since the closed-form solution can lose precision, especially for rays that only just touch a surface, where two roots nearly coincide.
+fn solve_quartic(b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    // Substitute x = y - b/4 to get the depressed quartic y⁴ + p y² + q y + r.
+    let shift = -b / 4.;
+    let b2 = b * b;
+    let p = c - 3. * b2 / 8.;
+    let q = d - b * c / 2. + b2 * b / 8.;
+    let r = e - b * d / 4. + b2 * c / 16. - 3. * b2 * b2 / 256.;
+
+    let mut roots = Vec::with_capacity(4);
+    let scale = p.abs().max(r.abs().sqrt()).max(1e-300);
+    if q.abs() < 1e-12 * scale * scale.sqrt() {
+        // Biquadratic: a quadratic in y².
+        for z in solve_quadratic(p, r) {
+            if z >= 0. {
+                let y = z.sqrt();
+                roots.extend([-y, y]);
+            }
+        }
+    } else {
+        // Rewrite the quartic as (y² + p/2 + m)² = 2m y² - q y + m² + m p + p²/4 - r. The right-hand side is a perfect square, (s y - q/(2s))² with s = √(2m), when m is a root of the resolvent cubic, and there's always a positive root because q isn't 0. The quartic then splits into two quadratics.
+        let m = largest_cubic_root(p, p * p / 4. - r, -q * q / 8.);
+        let s = (2. * m).sqrt();
+        let offset = q / (2. * s);
+        roots.extend(solve_quadratic(-s, p / 2. + m + offset));
+        roots.extend(solve_quadratic(s, p / 2. + m - offset));
+    }
+
+    let quartic = |x: f64| (((x + b) * x + c) * x + d) * x + e;
+    let derivative = |x: f64| ((4. * x + 3. * b) * x + 2. * c) * x + d;
+    for root in &mut roots {
+        let mut x = *root + shift;
+        for _ in 0..3 {
+            let slope = derivative(x);
+            if slope == 0. {
+                break;
+            }
+            let step = quartic(x) / slope;
+            if !step.is_finite() {
+                break;
+            }
+            x -= step;
+        }
+        *root = x;
+    }
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+// Real roots of x² + b x + c. A double root that rounding error has pushed just short of touching zero still counts, so that rays grazing a surface aren't lost.
+fn solve_quadratic(b: f64, c: f64) -> Vec<f64> {
+    let discriminant = b * b / 4. - c;
+    let tolerance = 1e-12 * (b * b / 4. + c.abs());
+    if discriminant < -tolerance {
+        return Vec::new();
+    }
+
+    // Avoid subtracting nearly equal numbers: find the larger root in magnitude directly, and the other from their product, c.
+    let half_b = b / 2.;
+    let sqrt_d = discriminant.max(0.).sqrt();
+    let larger = -half_b - half_b.signum() * sqrt_d;
+    if larger == 0. {
+        return vec![0., 0.];
+    }
+    vec![larger, c / larger]
+}
+
+// The largest real root of x³ + a x² + b x + c.
+fn largest_cubic_root(a: f64, b: f64, c: f64) -> f64 {
+    // Substitute x = y - a/3 to get the depressed cubic y³ + p y + q.
+    let shift = -a / 3.;
+    let p = b - a * a / 3.;
+    let q = 2. * a * a * a / 27. - a * b / 3. + c;
+
+    let discriminant = q * q / 4. + p * p * p / 27.;
+    let y = if discriminant >= 0. {
+        // One real root (Cardano).
+        let sqrt_d = discriminant.sqrt();
+        (-q / 2. + sqrt_d).cbrt() + (-q / 2. - sqrt_d).cbrt()
+    } else {
+        // Three real roots (trigonometric method); the largest is the one with the smallest angle. Here p < 0.
+        let amplitude = 2. * (-p / 3.).sqrt();
+        let angle = (3. * q / (p * amplitude)).clamp(-1., 1.).acos() / 3.;
+        amplitude * angle.cos()
+    };
+
+    let cubic = |x: f64| ((x + a) * x + b) * x + c;
+    let derivative = |x: f64| (3. * x + 2. * a) * x + b;
+    let mut x = y + shift;
+    for _ in 0..2 {
+        let slope = derivative(x);
+        if slope == 0. {
+            break;
+        }
+        x -= cubic(x) / slope;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{color::Color, materials::Lambertian};
+
+    // Lying flat, with the tube reaching from 1.5 to 2.5 units from the center and 0.5 units above and below.
+    fn torus() -> Torus {
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        Torus::new(
+            Point3::new(0., 0., 0.),
+            Direction::new(0., 1., 0.),
+            2.,
+            0.5,
+            material,
+        )
+    }
+
+    fn distance_from_surface(torus: &Torus, point: Point3) -> f64 {
+        let local = point - torus.center;
+        let height = local.dot(&torus.axis);
+        let radial = (local - height * torus.axis).length();
+        (radial - torus.major_radius).hypot(height) - torus.minor_radius
+    }
+
+    #[test]
+    fn ray_through_the_hole_hits_four_times() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let torus = torus();
+        let ray = Ray::new(Point3::new(-100., 0., 0.), Direction::new(1., 0., 0.));
+        let hits = torus.all_hits(&ray, &Interval::new(0.001, f64::INFINITY), &mut rng);
+        let xs: Vec<f64> = hits.iter().map(|hit| hit.point.x).collect();
+        let expected = [-2.5, -1.5, 1.5, 2.5];
+        assert_eq!(xs.len(), 4);
+        for (x, e) in xs.iter().zip(expected) {
+            assert!((x - e).abs() < 1e-9, "{:?}", xs);
+        }
+
+        let fronts: Vec<bool> = hits.iter().map(|hit| hit.front_face).collect();
+        assert_eq!(fronts, [true, false, true, false]);
+        assert!(vec3::approx_eq(
+            hits[1].outward_normal(),
+            Direction::new(1., 0., 0.),
+            1e-9
+        ));
+    }
+
+    #[test]
+    fn grazing_rays() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let torus = torus();
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        // Skimming the top of the tube, just below, the ray cuts through it twice on either side of the hole; just above, it misses.
+        for (height, count) in [(0.5 - 1e-6, 4), (0.5 + 1e-6, 0)] {
+            let ray = Ray::new(Point3::new(-10., height, 0.), Direction::new(1., 0., 0.));
+            let hits = torus.all_hits(&ray, &ray_t, &mut rng);
+            assert_eq!(hits.len(), count, "height {}", height);
+            for hit in &hits {
+                assert!(distance_from_surface(&torus, hit.point).abs() < 1e-9);
+                assert!((hit.point.x.abs() - 2.).abs() < 0.01);
+                assert!(hit.outward_normal().y > 0.99);
+            }
+        }
+
+        // Past the outside of the tube, at a slant, from far enough away that a naive solution would lose the hit to rounding error.
+        let direction = Direction::new(0., 0.3, 1.).normalize();
+        let touching = Point3::new(2.5 - 1e-6, 0., 0.);
+        let ray = Ray::new(touching - 1000. * direction, direction);
+        let hit = torus.hit(&ray, &ray_t, &mut rng).unwrap();
+        assert!(vec3::approx_eq(hit.point, touching, 1e-2));
+        assert!(distance_from_surface(&torus, hit.point).abs() < 1e-9);
+
+        let missing = Point3::new(2.5 + 1e-6, 0., 0.);
+        let ray = Ray::new(missing - 1000. * direction, direction);
+        assert!(torus.hit(&ray, &ray_t, &mut rng).is_none());
+    }
+
+    #[test]
+    fn ray_starting_inside_the_tube() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let torus = torus();
+        let ray = Ray::new(Point3::new(0., 0., 2.2), Direction::new(0., 0., -1.));
+        let hits = torus.all_hits(&ray, &Interval::new(0.001, f64::INFINITY), &mut rng);
+        let zs: Vec<f64> = hits.iter().map(|hit| hit.point.z).collect();
+        assert_eq!(zs.len(), 3, "{:?}", zs);
+        for (z, e) in zs.iter().zip([1.5, -1.5, -2.5]) {
+            assert!((z - e).abs() < 1e-9, "{:?}", zs);
+        }
+        assert!(!hits[0].front_face);
+        assert!(vec3::approx_eq(
+            hits[0].normal,
+            Direction::new(0., 0., 1.),
+            1e-9
+        ));
+    }
+
+    #[test]
+    fn uv_and_tangent() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let torus = torus();
+        let ray = Ray::new(Point3::new(2., 10., 0.), Direction::new(0., -1., 0.));
+        let hit = torus
+            .hit(&ray, &Interval::new(0.001, f64::INFINITY), &mut rng)
+            .unwrap();
+
+        // The top of the tube is a quarter of the way round it from the outer equator.
+        assert!((hit.v - 0.25).abs() < 1e-9);
+        assert!(hit.tangent.dot(&hit.outward_normal()).abs() < 1e-9);
+        assert!(vec3::approx_eq(
+            hit.tangent,
+            torus.axis.cross(&Direction::new(1., 0., 0.)),
+            1e-9
+        ));
+    }
+}
@@ -0,0 +1,240 @@
+use rand::rngs::SmallRng;
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable},
+    interval::Interval,
+    motion::Motion,
+    ray::Ray,
+    vec3::{
+        self, Direction, Point3,
+        matrix::{Mat3, Mat4},
+        quaternion::Quat,
+    },
+};
+
+// A shape moved, turned or stretched by an affine transformation. Rays are carried into the shape's own coordinates, where it's hit as usual, and the hit is carried back out again. Since `H` can be an `Arc<dyn Hittable>`, one shape, however complicated, can be placed in the scene many times without being rebuilt.
+pub struct Transformed<H: Hittable> {
+    object: H,
+    to_world: Mat4,
+    to_object: Mat4, // The inverse of `to_world`.
+    motion: Motion,
+}
+
+impl<H: Hittable> Transformed<H> {
+    // Start with the identity transformation and build it up with the methods below, each of which is applied after the ones before.
+    pub fn new(object: H) -> Self {
+        Self {
+            object,
+            to_world: Mat4::IDENTITY,
+            to_object: Mat4::IDENTITY,
+            motion: Motion::none(),
+        }
+    }
+
+    // Move the transformed shape while the shutter is open. The motion is in world coordinates, after all the transformations, whichever order they're applied in.
+    pub fn with_motion(mut self, motion: Motion) -> Self {
+        self.motion = motion;
+        self
+    }
+
+    pub fn translate(self, offset: Direction) -> Self {
+        self.then(Mat4::translation(offset), Mat4::translation(-offset))
+    }
+
+    // Rotate counterclockwise, looking down `axis` towards the origin.
+    pub fn rotate_axis_angle(self, axis: Direction, angle_in_degrees: f64) -> Self {
+        self.rotate(Quat::from_axis_angle(axis, angle_in_degrees.to_radians()))
+    }
+
+    pub fn rotate(self, rotation: Quat) -> Self {
+        self.then(
+            Mat4::from_linear(rotation.to_mat3()),
+            Mat4::from_linear(rotation.conjugate().to_mat3()),
+        )
+    }
+
+    // Scale by a different factor along each axis. Negative factors mirror the shape.
+    pub fn scale(self, x: f64, y: f64, z: f64) -> Self {
+        assert!(
+            [x, y, z].iter().all(|factor| 1e-8 < factor.abs()),
+            "Scale factor is too small"
+        );
+        self.then(
+            Mat4::from_linear(Mat3::scaling(x, y, z)),
+            Mat4::from_linear(Mat3::scaling(1. / x, 1. / y, 1. / z)),
+        )
+    }
+
+    // Apply any invertible affine map, e.g. a shear.
+    pub fn transform(self, matrix: Mat4) -> Self {
+        let inverse = matrix.inverse().expect("Matrix is not invertible");
+        self.then(matrix, inverse)
+    }
+
+    fn then(mut self, matrix: Mat4, inverse: Mat4) -> Self {
+        self.to_world = matrix * self.to_world;
+        self.to_object = self.to_object * inverse;
+        self
+    }
+}
+
+impl<H: Hittable> Hittable for Transformed<H> {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, rng: &mut SmallRng) -> Option<HitRecord> {
+        // Moving the shape is the same as moving the ray the opposite way.
+        let offset = self.motion.offset(ray.time);
+
+        // Rays have unit directions, so distances along the ray in object space are `stretch` times those in world space.
+        let direction = self.to_object * ray.direction;
+        let stretch = direction.length();
+        let object_ray =
+            Ray::new(self.to_object * (ray.origin - offset), direction).with_time(ray.time);
+        let object_t = Interval::new(ray_t.min * stretch, ray_t.max * stretch);
+
+        let mut record = self.object.hit(&object_ray, &object_t, rng)?;
+
+        // Normals must stay perpendicular to the surface, so they're transformed by the inverse transpose. This also keeps them on the same side of the surface as the ray, so `front_face` is unchanged.
+        record.t /= stretch;
+        record.point = self.to_world * record.point + offset;
+        record.normal = (self.to_object.linear.transpose() * record.normal).normalize();
+
+        let tangent = self.to_world * record.tangent;
+        [record.tangent, record.bitangent] = vec3::orthonormal_basis_2d(&record.outward_normal());
+        Some(record.with_tangent(tangent))
+    }
+
+    // The box around the transformed corners of the object's box, swept along its path if it moves.
+    fn bounding_box(&self) -> Aabb {
+        let bbox = self.object.bounding_box();
+        if bbox == Aabb::EMPTY || !bbox.is_bounded() {
+            return bbox;
+        }
+
+        let corners = (0..8).map(|corner| {
+            let pick = |axis: usize| {
+                let interval = bbox.axis_interval(axis);
+                if corner >> axis & 1 == 0 {
+                    interval.min
+                } else {
+                    interval.max
+                }
+            };
+            self.to_world * Point3::new(pick(0), pick(1), pick(2))
+        });
+        let mut minimum = Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut maximum = -minimum;
+        for corner in corners {
+            for axis in 0..3 {
+                minimum[axis] = minimum[axis].min(corner[axis]);
+                maximum[axis] = maximum[axis].max(corner[axis]);
+            }
+        }
+        self.motion.sweep(&Aabb::from_points(minimum, maximum))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{color::Color, hittables::sphere::Sphere, materials::Lambertian};
+
+    fn unit_sphere() -> Arc<dyn Hittable> {
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        Arc::new(Sphere::new(Point3::new(0., 0., 0.), 1., material))
+    }
+
+    #[test]
+    fn stretched_sphere_is_an_ellipsoid() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let ellipsoid = Transformed::new(unit_sphere())
+            .scale(2., 1., 1.)
+            .translate(Direction::new(0., 0., -5.));
+        let ray_t = Interval::new(0., f64::INFINITY);
+
+        // Along the long axis, the surface is 2 units from the center.
+        let ray = Ray::new(Point3::new(10., 0., -5.), Direction::new(-1., 0., 0.));
+        let record = ellipsoid.hit(&ray, &ray_t, &mut rng).unwrap();
+        assert!((record.t - 8.).abs() < 1e-9);
+        assert!(vec3::approx_eq(
+            record.point,
+            Point3::new(2., 0., -5.),
+            1e-9
+        ));
+        assert!(vec3::approx_eq(
+            record.normal,
+            Direction::new(1., 0., 0.),
+            1e-9
+        ));
+
+        // Off axis, the normal is tilted towards the long axis compared with a sphere's.
+        let ray = Ray::new(Point3::new(1., 5., -4.5), Direction::new(0., -1., 0.));
+        let record = ellipsoid.hit(&ray, &ray_t, &mut rng).unwrap();
+        assert!((record.t - (5. - 0.5f64.sqrt())).abs() < 1e-9);
+        let expected = Direction::new(0.25, 0.5f64.sqrt(), 0.5).normalize();
+        assert!(vec3::approx_eq(record.outward_normal(), expected, 1e-9));
+        assert!(record.front_face);
+    }
+
+    #[test]
+    fn rotation_moves_the_bounding_box() {
+        let bar = Transformed::new(unit_sphere())
+            .scale(3., 1., 1.)
+            .rotate_axis_angle(Direction::new(0., 0., 1.), 90.)
+            .translate(Direction::new(1., 0., 0.));
+        let bbox = bar.bounding_box();
+        assert!((bbox.x.min - 0.).abs() < 1e-9 && (bbox.x.max - 2.).abs() < 1e-9);
+        assert!((bbox.y.min + 3.).abs() < 1e-9 && (bbox.y.max - 3.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn instances_share_one_object() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let sphere = unit_sphere();
+        let left = Transformed::new(sphere.clone()).translate(Direction::new(-3., 0., 0.));
+        let right = Transformed::new(sphere).translate(Direction::new(3., 0., 0.));
+        let ray_t = Interval::new(0., f64::INFINITY);
+        for (instance, x) in [(&left, -3.), (&right, 3.)] {
+            let ray = Ray::new(Point3::new(x, 0., 5.), Direction::new(0., 0., -1.));
+            let record = instance.hit(&ray, &ray_t, &mut rng).unwrap();
+            assert!((record.t - 4.).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn moving_instances_are_where_they_are_at_the_time() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let ball = Transformed::new(unit_sphere())
+            .scale(1., 2., 1.)
+            .with_motion(Motion::linear(Direction::new(4., 0., 0.)));
+        let ray_t = Interval::new(0., f64::INFINITY);
+        let ray = |time: f64| {
+            Ray::new(Point3::new(2., 0., 5.), Direction::new(0., 0., -1.)).with_time(time)
+        };
+        assert!(ball.hit(&ray(0.), &ray_t, &mut rng).is_none());
+        let record = ball.hit(&ray(0.5), &ray_t, &mut rng).unwrap();
+        assert!(vec3::approx_eq(record.point, Point3::new(2., 0., 1.), 1e-9));
+        assert!(vec3::approx_eq(
+            record.normal,
+            Direction::new(0., 0., 1.),
+            1e-9
+        ));
+
+        // The box covers the whole path.
+        let bbox = ball.bounding_box();
+        assert!((bbox.x.min + 1.).abs() < 1e-9 && (bbox.x.max - 5.).abs() < 1e-9);
+        assert!((bbox.y.min + 2.).abs() < 1e-9 && (bbox.y.max - 2.).abs() < 1e-9);
+
+        // The time is passed on to a moving shape inside.
+        let material = Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+        let falling = Sphere::new(Point3::new(0., 0., 0.), 1., material)
+            .with_motion(Motion::linear(Direction::new(0., -4., 0.)));
+        let falling = Transformed::new(falling).translate(Direction::new(2., 2., 0.));
+        assert!(falling.hit(&ray(0.), &ray_t, &mut rng).is_none());
+        let record = falling.hit(&ray(0.5), &ray_t, &mut rng).unwrap();
+        assert!(vec3::approx_eq(record.point, Point3::new(2., 0., 1.), 1e-9));
+    }
+}
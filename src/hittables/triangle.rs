@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use rand::{Rng, rngs::SmallRng};
+
+use crate::{
+    aabb::Aabb,
+    hittables::{HitRecord, Hittable},
+    interval::Interval,
+    materials::Material,
+    ray::Ray,
+    vec3::{Direction, Point3},
+};
+
+pub struct Triangle {
+    pub vertices: [Point3; 3],
+    pub normals: Option<[Direction; 3]>, // Per-vertex normals for smooth shading.
+    pub material: Arc<dyn Material>,
+}
+
+impl Triangle {
+    // A flat triangle. Its outward normal follows the right-hand rule: with `a`, `b`, `c` counterclockwise, it points towards the viewer.
+    pub fn new(a: Point3, b: Point3, c: Point3, material: Arc<dyn Material>) -> Self {
+        assert!(
+            !(b - a).cross(&(c - a)).near_zero(),
+            "Normal vector too close to zero: vertices too close to collinear?"
+        );
+        Self {
+            vertices: [a, b, c],
+            normals: None,
+            material,
+        }
+    }
+
+    // A triangle whose normal is interpolated between the ones given at each vertex.
+    pub fn with_normals(
+        vertices: [Point3; 3],
+        normals: [Direction; 3],
+        material: Arc<dyn Material>,
+    ) -> Self {
+        let [a, b, c] = vertices;
+        let mut triangle = Self::new(a, b, c, material);
+        assert!(
+            normals.iter().all(|normal| !normal.near_zero()),
+            "Vertex normal(s) too close to zero"
+        );
+        triangle.normals = Some(normals.map(|normal| normal.normalize()));
+        triangle
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, _rng: &mut SmallRng) -> Option<HitRecord> {
+        let hit = intersect(&self.vertices, ray, ray_t)?;
+        let outward_normal = match &self.normals {
+            Some(normals) => interpolate(normals, hit.beta, hit.gamma),
+            None => hit.geometric_normal,
+        };
+
+        // Without texture coordinates of its own, the triangle is mapped onto half of the unit square by its barycentric coordinates, with the first vertex at the origin.
+        let [a, b, _] = self.vertices;
+        Some(
+            HitRecord::new(
+                ray.at(hit.t),
+                outward_normal,
+                hit.t,
+                self.material.clone(),
+                ray,
+            )
+            .with_uv(hit.beta, hit.gamma)
+            .with_tangent(b - a),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        bounding_box(&self.vertices)
+    }
+
+    fn area(&self) -> f64 {
+        let [a, b, c] = self.vertices;
+        0.5 * (b - a).cross(&(c - a)).length()
+    }
+
+    fn sample_surface(&self, _time: f64, rng: &mut SmallRng) -> Option<Point3> {
+        // Uniform barycentric coordinates: pick a point in the unit square, and fold the half outside the triangle back in.
+        let [a, b, c] = self.vertices;
+        let (mut beta, mut gamma) = (rng.random::<f64>(), rng.random::<f64>());
+        if beta + gamma > 1. {
+            (beta, gamma) = (1. - beta, 1. - gamma);
+        }
+        Some(a + beta * (b - a) + gamma * (c - a))
+    }
+
+    // As the default, but measuring the tilt against the flat triangle, even if its normals are smoothed.
+    fn pdf_value(
+        &self,
+        origin: &Point3,
+        direction: &Direction,
+        time: f64,
+        _rng: &mut SmallRng,
+    ) -> f64 {
+        let ray = Ray::new(*origin, *direction).with_time(time);
+        let Some(hit) = intersect(&self.vertices, &ray, &Interval::new(0.001, f64::INFINITY))
+        else {
+            return 0.;
+        };
+        let cosine = ray.direction.dot(&hit.geometric_normal).abs();
+        hit.t * hit.t / (cosine * self.area())
+    }
+}
+
+pub(crate) struct TriangleHit {
+    pub t: f64,
+    pub beta: f64,  // Barycentric weight of the second vertex.
+    pub gamma: f64, // Barycentric weight of the third vertex.
+    pub geometric_normal: Direction,
+}
+
+// Möller–Trumbore: solve origin + t * direction = a + beta * (b - a) + gamma * (c - a) for t, beta and gamma by Cramer's rule, rejecting the hit if the barycentric coordinates put the point outside the triangle.
+pub(crate) fn intersect(
+    vertices: &[Point3; 3],
+    ray: &Ray,
+    ray_t: &Interval,
+) -> Option<TriangleHit> {
+    let [a, b, c] = vertices;
+    let edge_1 = *b - *a;
+    let edge_2 = *c - *a;
+
+    let p = ray.direction.cross(&edge_2);
+    let determinant = edge_1.dot(&p);
+
+    // Return no hit if ray is parallel to the plane of the triangle.
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+    let inverse_determinant = 1. / determinant;
+
+    let s = ray.origin - *a;
+    let beta = s.dot(&p) * inverse_determinant;
+    if !(0.0..=1.0).contains(&beta) {
+        return None;
+    }
+
+    let q = s.cross(&edge_1);
+    let gamma = ray.direction.dot(&q) * inverse_determinant;
+    if gamma < 0. || beta + gamma > 1. {
+        return None;
+    }
+
+    let t = edge_2.dot(&q) * inverse_determinant;
+    if !ray_t.contains(t) {
+        return None;
+    }
+
+    Some(TriangleHit {
+        t,
+        beta,
+        gamma,
+        geometric_normal: edge_1.cross(&edge_2).normalize(),
+    })
+}
+
+pub(crate) fn interpolate(normals: &[Direction; 3], beta: f64, gamma: f64) -> Direction {
+    let alpha = 1. - beta - gamma;
+    let normal = alpha * normals[0] + beta * normals[1] + gamma * normals[2];
+    if normal.near_zero() {
+        normals[0]
+    } else {
+        normal.normalize()
+    }
+}
+
+// The direction in which the texture coordinate u increases across the triangle, found by solving edge = du * tangent + dv * bitangent for both edges from the first vertex. None if the texture coordinates are degenerate.
+pub(crate) fn texture_tangent(
+    vertices: &[Point3; 3],
+    texcoords: &[[f64; 2]; 3],
+) -> Option<Direction> {
+    let [a, b, c] = vertices;
+    let (edge_1, edge_2) = (*b - *a, *c - *a);
+    let [[u_a, v_a], [u_b, v_b], [u_c, v_c]] = texcoords;
+    let (du_1, dv_1) = (u_b - u_a, v_b - v_a);
+    let (du_2, dv_2) = (u_c - u_a, v_c - v_a);
+
+    let determinant = du_1 * dv_2 - du_2 * dv_1;
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+    Some((dv_2 * edge_1 - dv_1 * edge_2) / determinant)
+}
+
+pub(crate) fn bounding_box(vertices: &[Point3; 3]) -> Aabb {
+    let [a, b, c] = vertices;
+    Aabb::surrounding(&Aabb::from_points(*a, *b), &Aabb::from_points(*a, *c))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{color::Color, materials::Lambertian, vec3};
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            Point3::new(0., 0., 0.),
+            Point3::new(1., 0., 0.),
+            Point3::new(0., 1., 0.),
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    #[test]
+    fn hit_inside_gives_barycentrics() {
+        let ray = Ray::new(Point3::new(0.25, 0.5, 1.), Direction::new(0., 0., -1.));
+        let hit = intersect(&triangle().vertices, &ray, &Interval::UNIT).unwrap();
+        assert!((hit.t - 1.).abs() < 1e-12);
+        assert!((hit.beta - 0.25).abs() < 1e-12);
+        assert!((hit.gamma - 0.5).abs() < 1e-12);
+        assert!(vec3::approx_eq(
+            hit.geometric_normal,
+            Direction::new(0., 0., 1.),
+            1e-12
+        ));
+    }
+
+    #[test]
+    fn miss_outside_edges() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let triangle = triangle();
+        let ray_t = Interval::new(0., f64::INFINITY);
+        for origin in [
+            Point3::new(0.6, 0.6, 1.),
+            Point3::new(-0.1, 0.5, 1.),
+            Point3::new(0.5, -0.1, 1.),
+        ] {
+            let ray = Ray::new(origin, Direction::new(0., 0., -1.));
+            assert!(triangle.hit(&ray, &ray_t, &mut rng).is_none());
+        }
+    }
+
+    #[test]
+    fn smooth_normals_are_interpolated() {
+        let normals = [
+            Direction::new(-1., 0., 1.),
+            Direction::new(1., 0., 1.),
+            Direction::new(0., 0., 1.),
+        ];
+        let normals = normals.map(|normal| normal.normalize());
+        let normal = interpolate(&normals, 0.5, 0.);
+        assert!(vec3::approx_eq(normal, Direction::new(0., 0., 1.), 1e-12));
+    }
+
+    #[test]
+    fn texture_tangent_follows_increasing_u() {
+        let vertices = triangle().vertices;
+        // The texture is rotated a quarter turn: u increases along y, v along -x.
+        let texcoords = [[0., 1.], [0., 0.], [1., 1.]];
+        let tangent = texture_tangent(&vertices, &texcoords).unwrap();
+        assert!(vec3::approx_eq(tangent, Direction::new(0., 1., 0.), 1e-12));
+        assert!(texture_tangent(&vertices, &[[0., 0.], [1., 1.], [2., 2.]]).is_none());
+    }
+
+    #[test]
+    fn tangent_frame_is_right_handed() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let ray = Ray::new(Point3::new(0.25, 0.5, -1.), Direction::new(0., 0., 1.));
+        let ray_t = Interval::new(0., f64::INFINITY);
+        let record = triangle().hit(&ray, &ray_t, &mut rng).unwrap();
+        assert!(!record.front_face);
+        assert_eq!((record.u, record.v), (0.25, 0.5));
+        assert!(vec3::approx_eq(
+            record.tangent,
+            Direction::new(1., 0., 0.),
+            1e-12
+        ));
+        assert!(vec3::approx_eq(
+            record.tangent.cross(&record.bitangent),
+            record.outward_normal(),
+            1e-12
+        ));
+    }
+}
@@ -1,13 +1,14 @@
-use std::sync::Arc;
+use std::{f64::consts::PI, sync::Arc};
 
 use rand::rngs::SmallRng;
 
 use crate::{
+    aabb::Aabb,
     hittables::{HitRecord, Hittable},
     interval::Interval,
     materials::Material,
     ray::Ray,
-    vec3::{Direction, Point3},
+    vec3::{self, Direction, Point3},
 };
 
 pub struct Tube {
@@ -16,6 +17,7 @@ pub struct Tube {
     pub radius: f64,
     pub height: f64,
     pub material: Arc<dyn Material>,
+    reference: [Direction; 2], // Perpendicular to the axis: angles around it are measured from the first towards the second.
 }
 
 impl Tube {
@@ -33,6 +35,7 @@ impl Tube {
             radius,
             height,
             material,
+            reference: vec3::orthonormal_basis_2d(&axis),
         }
     }
 }
@@ -79,7 +82,17 @@ impl Hittable for Tube {
             let projection = self.center_of_base + height_along_axis * axis;
             let outward_normal = (point - projection).normalize();
 
-            let record = HitRecord::new(point, outward_normal, t, self.material.clone(), ray);
+            // u is the angle around the axis as a fraction of a full turn, v the height as a fraction of the tube's length.
+            let [reference_u, reference_v] = &self.reference;
+            let angle = outward_normal
+                .dot(reference_v)
+                .atan2(outward_normal.dot(reference_u));
+            let u = angle.rem_euclid(2. * PI) / (2. * PI);
+            let v = height_along_axis / self.height;
+
+            let record = HitRecord::new(point, outward_normal, t, self.material.clone(), ray)
+                .with_uv(u, v)
+                .with_tangent(axis.cross(&outward_normal));
 
             hit_record = Some(record);
             break;
@@ -87,4 +100,19 @@ impl Hittable for Tube {
 
         hit_record
     }
+
+    // The tube lies within the boxes of its two end circles.
+    fn bounding_box(&self) -> Aabb {
+        let extent = |a: f64| self.radius * (1. - a * a).max(0.).sqrt();
+        let extent = Direction::new(
+            extent(self.axis.x),
+            extent(self.axis.y),
+            extent(self.axis.z),
+        );
+        let top = self.center_of_base + self.height * self.axis;
+        Aabb::surrounding(
+            &Aabb::around(self.center_of_base, extent),
+            &Aabb::around(top, extent),
+        )
+    }
 }
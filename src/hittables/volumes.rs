@@ -3,11 +3,14 @@ use std::sync::Arc;
 use rand::{Rng, rngs::SmallRng};
 
 use crate::{
+    aabb::Aabb,
     color::Color,
     hittables::{HitRecord, Hittable},
     interval::Interval,
     materials::Isotropic,
+    noise::ScalarField,
     ray::Ray,
+    textures::{SolidColor, Texture},
     vec3::Direction,
 };
 
@@ -15,14 +18,37 @@ pub struct ConstantMedium {
     boundary: Arc<dyn Hittable>, // Assumed to be convex!
     negative_inverse_density: f64,
     phase_function: Arc<Isotropic>, // `Isotropic` as a `Material`.
+    density_field: Option<Arc<dyn ScalarField>>, // Fraction of the full density at each point; everywhere 1 if `None`.
 }
 
 impl ConstantMedium {
     pub fn new(boundary: Arc<dyn Hittable>, color: Color, density: f64) -> Self {
+        Self::from_texture(boundary, Arc::new(SolidColor::new(color)), density)
+    }
+
+    pub fn from_texture(
+        boundary: Arc<dyn Hittable>,
+        texture: Arc<dyn Texture>,
+        density: f64,
+    ) -> Self {
         Self {
             boundary,
             negative_inverse_density: -1.0 / density,
-            phase_function: Arc::new(Isotropic::new(color)),
+            phase_function: Arc::new(Isotropic::from_texture(texture)),
+            density_field: None,
+        }
+    }
+
+    // A medium whose density varies from point to point: `density` times the value of `field`, clamped to [0, 1], e.g. a `NoiseField` for wisps of smoke or cloud.
+    pub fn with_density_field(
+        boundary: Arc<dyn Hittable>,
+        texture: Arc<dyn Texture>,
+        density: f64,
+        field: Arc<dyn ScalarField>,
+    ) -> Self {
+        Self {
+            density_field: Some(field),
+            ..Self::from_texture(boundary, texture, density)
         }
     }
 }
@@ -46,17 +72,23 @@ impl Hittable for ConstantMedium {
 
         t1 = t1.max(0.0);
 
-        let distance_inside_boundary = t2 - t1;
+        // Delta tracking: take steps as if the medium had its full density everywhere, and accept each tentative scattering event with probability equal to the fraction of that density actually present there; otherwise carry on from that point. With a constant density, the first event is always accepted.
+        let mut t = t1;
+        let point = loop {
+            let u: f64 = rng.random_range(0.0..1.0);
+            t += self.negative_inverse_density * u.ln();
 
-        let u: f64 = rng.random_range(0.0..1.0);
-        let hit_distance = self.negative_inverse_density * u.ln();
+            if t > t2 {
+                return None;
+            }
 
-        if hit_distance > distance_inside_boundary {
-            return None;
-        }
-
-        let t = t1 + hit_distance;
-        let point = ray.at(t);
+            let point = ray.at(t);
+            match &self.density_field {
+                Some(field)
+                    if rng.random_range(0.0..1.0) >= Interval::UNIT.clamp(field.value(&point)) => {}
+                _ => break point,
+            }
+        };
 
         Some(HitRecord {
             t,
@@ -64,6 +96,14 @@ impl Hittable for ConstantMedium {
             normal: Direction::new(1.0, 0.0, 0.0), // Arbitrary.
             front_face: true,                      // Arbitrary.
             material: self.phase_function.clone(),
+            u: 0.,
+            v: 0.,
+            tangent: Direction::new(0.0, 1.0, 0.0), // Arbitrary.
+            bitangent: Direction::new(0.0, 0.0, 1.0), // Arbitrary.
         })
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
 }
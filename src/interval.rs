@@ -1,5 +1,4 @@
-use std::f64::{INFINITY, NEG_INFINITY};
-
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Interval {
     pub min: f64,
     pub max: f64,
@@ -7,13 +6,13 @@ pub struct Interval {
 
 impl Interval {
     pub const EMPTY: Interval = Interval {
-        min: INFINITY,
-        max: NEG_INFINITY,
+        min: f64::INFINITY,
+        max: f64::NEG_INFINITY,
     };
 
     pub const FULL: Interval = Interval {
-        min: NEG_INFINITY,
-        max: INFINITY,
+        min: f64::NEG_INFINITY,
+        max: f64::INFINITY,
     };
 
     pub const UNIT: Interval = Interval { min: 0.0, max: 1.0 };
@@ -22,6 +21,22 @@ impl Interval {
         Self { min, max }
     }
 
+    // The smallest interval containing both `a` and `b`.
+    pub fn enclosing(a: &Interval, b: &Interval) -> Self {
+        Self {
+            min: a.min.min(b.min),
+            max: a.max.max(b.max),
+        }
+    }
+
+    // The values in both `a` and `b`, which is empty if they don't overlap.
+    pub fn overlap(a: &Interval, b: &Interval) -> Self {
+        Self {
+            min: a.min.max(b.min),
+            max: a.max.min(b.max),
+        }
+    }
+
     pub fn size(&self) -> f64 {
         (self.max - self.min).max(0.0)
     }
@@ -37,4 +52,20 @@ impl Interval {
     pub fn clamp(&self, value: f64) -> f64 {
         value.clamp(self.min, self.max)
     }
+
+    pub fn shift(&self, offset: f64) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    // Pad the interval by `delta` in total, half on each side.
+    pub fn expand(&self, delta: f64) -> Self {
+        let padding = delta / 2.;
+        Self {
+            min: self.min - padding,
+            max: self.max + padding,
+        }
+    }
 }
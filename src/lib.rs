@@ -1,3 +1,4 @@
+pub mod aabb;
 pub mod camera;
 pub mod color;
 pub mod examples;
@@ -5,9 +6,16 @@ pub mod file;
 pub mod hittables;
 pub mod image;
 pub mod interval;
+pub mod loader;
 pub mod materials;
+pub mod motion;
+pub mod noise;
 pub mod particles;
+pub mod pdf;
 pub mod progress;
 pub mod ray;
+pub mod sdf;
+pub mod spectrum;
+pub mod textures;
 pub mod vec3;
 pub mod viewport;
@@ -0,0 +1,169 @@
+pub mod image;
+pub mod mtl;
+pub mod obj;
+
+use std::{
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        line: usize, // Counting from 1.
+        message: String,
+    },
+    Format {
+        path: PathBuf,
+        message: String, // What's wrong with a file that isn't organized in lines.
+    },
+}
+
+impl LoadError {
+    pub(crate) fn parse(path: &Path, line: usize, message: impl Into<String>) -> Self {
+        LoadError::Parse {
+            path: path.to_path_buf(),
+            line,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn format(path: &Path, message: impl Into<String>) -> Self {
+        LoadError::Format {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io { path, source } => {
+                write!(f, "failed to read {}: {}", path.display(), source)
+            }
+            LoadError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            LoadError::Format { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io { source, .. } => Some(source),
+            LoadError::Parse { .. } | LoadError::Format { .. } => None,
+        }
+    }
+}
+
+pub(crate) fn read_to_string(path: &Path) -> Result<String, LoadError> {
+    fs::read_to_string(path).map_err(|source| LoadError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+pub(crate) fn read(path: &Path) -> Result<Vec<u8>, LoadError> {
+    fs::read(path).map_err(|source| LoadError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+// A nonblank line of an OBJ or MTL file, split into its leading keyword and the arguments that follow, with any comment removed.
+pub(crate) struct Statement<'a> {
+    pub line: usize,
+    pub keyword: &'a str,
+    pub arguments: Vec<&'a str>,
+}
+
+pub(crate) fn statements(source: &str) -> impl Iterator<Item = Statement<'_>> {
+    source.lines().enumerate().filter_map(|(i, line)| {
+        let line = line.split('#').next().unwrap_or("");
+        let mut words = line.split_whitespace();
+        let keyword = words.next()?;
+        Some(Statement {
+            line: i + 1,
+            keyword,
+            arguments: words.collect(),
+        })
+    })
+}
+
+impl Statement<'_> {
+    // Parse exactly `N` numbers.
+    pub fn numbers<const N: usize>(&self, path: &Path) -> Result<[f64; N], LoadError> {
+        if self.arguments.len() != N {
+            return Err(LoadError::parse(
+                path,
+                self.line,
+                format!(
+                    "`{}` expects {} number(s), found {}",
+                    self.keyword,
+                    N,
+                    self.arguments.len()
+                ),
+            ));
+        }
+        let mut numbers = [0.; N];
+        for (number, argument) in numbers.iter_mut().zip(&self.arguments) {
+            *number = self.number(path, argument)?;
+        }
+        Ok(numbers)
+    }
+
+    // Parse at least `N` numbers, ignoring any extra ones, as for the optional w component of an OBJ vertex.
+    pub fn leading_numbers<const N: usize>(&self, path: &Path) -> Result<[f64; N], LoadError> {
+        if self.arguments.len() < N {
+            return Err(LoadError::parse(
+                path,
+                self.line,
+                format!(
+                    "`{}` expects at least {} number(s), found {}",
+                    self.keyword,
+                    N,
+                    self.arguments.len()
+                ),
+            ));
+        }
+        let mut numbers = [0.; N];
+        for (number, argument) in numbers.iter_mut().zip(&self.arguments) {
+            *number = self.number(path, argument)?;
+        }
+        Ok(numbers)
+    }
+
+    // The rest of the line, for names that may contain spaces.
+    pub fn name(&self, path: &Path) -> Result<String, LoadError> {
+        if self.arguments.is_empty() {
+            return Err(LoadError::parse(
+                path,
+                self.line,
+                format!("`{}` expects a name", self.keyword),
+            ));
+        }
+        Ok(self.arguments.join(" "))
+    }
+
+    fn number(&self, path: &Path, argument: &str) -> Result<f64, LoadError> {
+        match argument.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(number),
+            _ => Err(LoadError::parse(
+                path,
+                self.line,
+                format!("invalid number `{}`", argument),
+            )),
+        }
+    }
+}
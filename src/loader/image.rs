@@ -0,0 +1,278 @@
+use std::{io::Cursor, path::Path};
+
+use crate::{
+    color::Color,
+    loader::{self, LoadError},
+    textures::ImageTexture,
+};
+
+// Load a PPM (P3 or P6) or PNG file as a texture, recognized by its first bytes rather than its extension. Values are taken to be sRGB-encoded, as image files usually are, and converted to linear light, which is what the renderer works in.
+pub fn load(path: impl AsRef<Path>) -> Result<ImageTexture, LoadError> {
+    let path = path.as_ref();
+    let bytes = loader::read(path)?;
+    decode(&bytes, path)
+}
+
+// Decode the contents of a PPM or PNG file. `path` is only used to identify the source in error messages.
+pub fn decode(bytes: &[u8], path: &Path) -> Result<ImageTexture, LoadError> {
+    let (width, height, values) = if bytes.starts_with(PNG_SIGNATURE) {
+        decode_png(bytes, path)?
+    } else if bytes.starts_with(b"P3") || bytes.starts_with(b"P6") {
+        decode_ppm(bytes, path)?
+    } else {
+        return Err(LoadError::format(
+            path,
+            "unrecognized image format: expected PPM (P3 or P6) or PNG",
+        ));
+    };
+
+    let pixels = values
+        .chunks_exact(3)
+        .map(|rgb| {
+            Color::new(
+                srgb_to_linear(rgb[0]),
+                srgb_to_linear(rgb[1]),
+                srgb_to_linear(rgb[2]),
+            )
+        })
+        .collect();
+    Ok(ImageTexture::new(width, height, pixels))
+}
+
+// The inverse of the sRGB transfer function, for a value in [0, 1].
+pub fn srgb_to_linear(value: f64) -> f64 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// The sRGB transfer function, for a value in [0, 1]: the inverse of `srgb_to_linear`.
+pub fn linear_to_srgb(value: f64) -> f64 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1. / 2.4) - 0.055
+    }
+}
+
+// Width, height, and red, green and blue for each pixel in turn, scaled to [0, 1].
+type Decoded = (usize, usize, Vec<f64>);
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+fn decode_png(bytes: &[u8], path: &Path) -> Result<Decoded, LoadError> {
+    let error = |error: png::DecodingError| LoadError::format(path, error.to_string());
+
+    // Expand palettes and low bit depths, and strip 16-bit samples to 8 bits, so that every sample is one byte.
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(error)?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(error)?;
+    let samples = &buffer[..info.buffer_size()];
+
+    let channels = info.color_type.samples();
+    let values = samples
+        .chunks_exact(info.line_size)
+        .flat_map(|row| row[..info.width as usize * channels].chunks_exact(channels))
+        .flat_map(|pixel| {
+            // Gray is copied to all three channels; alpha is ignored.
+            let rgb = match channels {
+                1 | 2 => [pixel[0]; 3],
+                _ => [pixel[0], pixel[1], pixel[2]],
+            };
+            rgb.map(|sample| sample as f64 / 255.)
+        })
+        .collect();
+
+    Ok((info.width as usize, info.height as usize, values))
+}
+
+// A PPM file starts with a header of four whitespace-separated fields: P3 or P6, width, height and the maximum sample value, with comments running from # to the end of a line. P3 follows with the samples written as decimal numbers, P6 with raw bytes after a single whitespace character, two bytes per sample, big-endian, if the maximum is over 255.
+fn decode_ppm(bytes: &[u8], path: &Path) -> Result<Decoded, LoadError> {
+    let mut tokens = Tokens { bytes, position: 0 };
+    let magic = tokens.next_token();
+    if magic != b"P3" && magic != b"P6" {
+        return Err(LoadError::format(
+            path,
+            "expected PPM magic number P3 or P6",
+        ));
+    }
+    let mut header = [0; 3];
+    for (field, name) in header.iter_mut().zip(["width", "height", "maximum value"]) {
+        *field = tokens.next_number(path, name)?;
+    }
+    let [width, height, maximum] = header;
+
+    if width == 0 || height == 0 {
+        return Err(LoadError::format(path, "image is empty"));
+    }
+    if !(1..=65535).contains(&maximum) {
+        return Err(LoadError::format(
+            path,
+            format!("maximum value {} out of range 1 to 65535", maximum),
+        ));
+    }
+
+    let count = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(3))
+        .ok_or_else(|| LoadError::format(path, "image is too large"))?;
+
+    let samples: Vec<usize> = if magic == b"P3" {
+        (0..count)
+            .map(|_| tokens.next_number(path, "sample"))
+            .collect::<Result<_, _>>()?
+    } else {
+        let data = &bytes[(tokens.position + 1).min(bytes.len())..];
+        let width_in_bytes = if maximum > 255 { 2 } else { 1 };
+        let byte_count = count
+            .checked_mul(width_in_bytes)
+            .ok_or_else(|| LoadError::format(path, "image is too large"))?;
+        if data.len() < byte_count {
+            return Err(LoadError::format(
+                path,
+                format!(
+                    "expected {} bytes of pixel data, found {}",
+                    byte_count,
+                    data.len()
+                ),
+            ));
+        }
+        data.chunks_exact(width_in_bytes)
+            .take(count)
+            .map(|sample| {
+                sample
+                    .iter()
+                    .fold(0, |value, &byte| value * 256 + byte as usize)
+            })
+            .collect()
+    };
+
+    if let Some(sample) = samples.iter().find(|&&sample| sample > maximum) {
+        return Err(LoadError::format(
+            path,
+            format!("sample {} exceeds maximum value {}", sample, maximum),
+        ));
+    }
+
+    let values = samples
+        .into_iter()
+        .map(|sample| sample as f64 / maximum as f64)
+        .collect();
+    Ok((width, height, values))
+}
+
+struct Tokens<'a> {
+    bytes: &'a [u8],
+    position: usize, // Just after the last token read.
+}
+
+impl<'a> Tokens<'a> {
+    fn next_token(&mut self) -> &'a [u8] {
+        loop {
+            match self.bytes.get(self.position) {
+                Some(byte) if byte.is_ascii_whitespace() => self.position += 1,
+                Some(b'#') => {
+                    while self
+                        .bytes
+                        .get(self.position)
+                        .is_some_and(|&byte| byte != b'\n')
+                    {
+                        self.position += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(|byte| !byte.is_ascii_whitespace() && *byte != b'#')
+        {
+            self.position += 1;
+        }
+        &self.bytes[start..self.position]
+    }
+
+    fn next_number(&mut self, path: &Path, name: &str) -> Result<usize, LoadError> {
+        let token = self.next_token();
+        if token.is_empty() {
+            return Err(LoadError::format(path, format!("missing {}", name)));
+        }
+        std::str::from_utf8(token)
+            .ok()
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| {
+                LoadError::format(
+                    path,
+                    format!("invalid {} `{}`", name, String::from_utf8_lossy(token)),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{textures::Texture, vec3::Point3};
+
+    fn reds(texture: &ImageTexture) -> Vec<f64> {
+        (0..texture.height())
+            .flat_map(|row| (0..texture.width()).map(move |column| (column, row)))
+            .map(|(column, row)| texture.pixel(column, row).r)
+            .collect()
+    }
+
+    #[test]
+    fn decode_ascii_and_binary_ppm() {
+        let ascii = b"P3\n# A comment.\n2 1\n255\n255 0 0  0 0 0\n";
+        let texture = decode(ascii, Path::new("ascii.ppm")).unwrap();
+        assert_eq!((texture.width(), texture.height()), (2, 1));
+        assert_eq!(reds(&texture), [1., 0.]);
+
+        let mut binary = b"P6 1 2 65535\n".to_vec();
+        binary.extend([0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let texture = decode(&binary, Path::new("binary.ppm")).unwrap();
+        assert_eq!(reds(&texture), [1., 0.]);
+    }
+
+    #[test]
+    fn decode_png_to_linear() {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer
+            .write_image_data(&[255, 0, 0, 188, 188, 188])
+            .unwrap();
+        writer.finish().unwrap();
+
+        let texture = decode(&bytes, Path::new("image.png")).unwrap();
+        let origin = Point3::new(0., 0., 0.);
+        assert_eq!(texture.value(0.25, 0.5, &origin), Color::new(1., 0., 0.));
+        assert!((texture.value(0.75, 0.5, &origin).g - 0.5).abs() < 0.01); // sRGB 188 is about half as bright as 255.
+    }
+
+    #[test]
+    fn bad_images_are_errors() {
+        let error = load("no/such/image.png").err().unwrap();
+        assert!(matches!(error, LoadError::Io { .. }));
+
+        for bytes in [
+            &b"GIF89a"[..],
+            b"P3 2 2 255 0 0 0",
+            b"P6 2 2 255\n\x00\x00",
+            b"P3 1 1 15 16 0 0",
+            // Three samples per pixel fit in a usize, but not at two bytes each.
+            b"P6 2147483648 2147483648 65535\n",
+        ] {
+            let error = decode(bytes, Path::new("bad")).err().unwrap();
+            assert!(matches!(error, LoadError::Format { .. }));
+        }
+    }
+}
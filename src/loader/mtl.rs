@@ -0,0 +1,150 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use crate::{
+    color::Color,
+    loader::{self, LoadError, Statement},
+    materials::{Dielectric, Lambertian, Light, Material, Metal},
+};
+
+// The properties of a material from an MTL file that this ray tracer understands. Other statements, such as texture maps, are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaterialDescription {
+    pub name: String,
+    pub diffuse: [f64; 3],               // Kd
+    pub specular: [f64; 3],              // Ks
+    pub shininess: f64,                  // Ns, in the range [0, 1000].
+    pub refraction_index: Option<f64>,   // Ni
+    pub emission: [f64; 3],              // Ke
+    pub dissolve: f64,                   // d, or 1 - Tr: 1.0 is opaque.
+    pub illumination_model: Option<u32>, // illum
+}
+
+impl MaterialDescription {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            diffuse: [0.8, 0.8, 0.8],
+            specular: [0., 0., 0.],
+            shininess: 0.,
+            refraction_index: None,
+            emission: [0., 0., 0.],
+            dissolve: 1.,
+            illumination_model: None,
+        }
+    }
+
+    /* Map onto the closest of this ray tracer's materials:
+    - anything emissive becomes a `Light`;
+    - anything transparent, or with one of the refractive illumination models, becomes a `Dielectric`;
+    - anything with illumination model 3 (ray-traced reflection), or whose specular color is brighter than its diffuse color, becomes a `Metal`, with a fuzziness that decreases as the Phong exponent, Ns, increases;
+    - anything else becomes a `Lambertian`.
+    */
+    pub fn to_material(&self) -> Arc<dyn Material> {
+        let to_color = |[r, g, b]: [f64; 3]| Color::new(r, g, b);
+        let brightness = |[r, g, b]: [f64; 3]| (r + g + b) / 3.;
+
+        if brightness(self.emission) > 0. {
+            return Arc::new(Light::new(to_color(self.emission)));
+        }
+
+        let refractive_model = matches!(self.illumination_model, Some(4 | 6 | 7 | 9));
+        if self.dissolve < 1. || refractive_model {
+            return Arc::new(Dielectric::new(self.refraction_index.unwrap_or(1.5)));
+        }
+
+        let reflective_model = self.illumination_model == Some(3);
+        if reflective_model || brightness(self.specular) > brightness(self.diffuse) {
+            let fuzz = (2. / (self.shininess + 2.)).sqrt();
+            return Arc::new(Metal::new(to_color(self.specular), fuzz));
+        }
+
+        Arc::new(Lambertian::new(to_color(self.diffuse)))
+    }
+}
+
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<MaterialDescription>, LoadError> {
+    let path = path.as_ref();
+    let source = loader::read_to_string(path)?;
+    parse(&source, path)
+}
+
+// `path` is only used to identify the source in error messages.
+pub fn parse(source: &str, path: &Path) -> Result<Vec<MaterialDescription>, LoadError> {
+    let mut descriptions: Vec<MaterialDescription> = Vec::new();
+
+    for statement in loader::statements(source) {
+        if statement.keyword == "newmtl" {
+            descriptions.push(MaterialDescription::new(statement.name(path)?));
+            continue;
+        }
+
+        let Some(description) = descriptions.last_mut() else {
+            return Err(LoadError::parse(
+                path,
+                statement.line,
+                format!("`{}` before any `newmtl`", statement.keyword),
+            ));
+        };
+
+        match statement.keyword {
+            "Kd" => description.diffuse = color(&statement, path)?,
+            "Ks" => description.specular = color(&statement, path)?,
+            "Ke" => description.emission = color(&statement, path)?,
+            "Ns" => {
+                let [shininess] = statement.numbers(path)?;
+                description.shininess = shininess.max(0.);
+            }
+            "Ni" => {
+                let [refraction_index] = statement.numbers(path)?;
+                if refraction_index < 1e-8 {
+                    return Err(LoadError::parse(
+                        path,
+                        statement.line,
+                        "refraction index must be positive",
+                    ));
+                }
+                description.refraction_index = Some(refraction_index);
+            }
+            "d" => {
+                let [dissolve] = statement.numbers(path)?;
+                description.dissolve = dissolve.clamp(0., 1.);
+            }
+            "Tr" => {
+                let [transparency] = statement.numbers(path)?;
+                description.dissolve = 1. - transparency.clamp(0., 1.);
+            }
+            "illum" => {
+                let [model] = statement.numbers(path)?;
+                if model < 0. || model.fract() != 0. {
+                    return Err(LoadError::parse(
+                        path,
+                        statement.line,
+                        format!("invalid illumination model `{}`", model),
+                    ));
+                }
+                description.illumination_model = Some(model as u32);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(descriptions)
+}
+
+// Materials from an MTL file by name, ready to hand to the OBJ loader.
+pub fn materials(descriptions: &[MaterialDescription]) -> HashMap<String, Arc<dyn Material>> {
+    descriptions
+        .iter()
+        .map(|description| (description.name.clone(), description.to_material()))
+        .collect()
+}
+
+// Colors may be given as one number, meaning a shade of gray, or three.
+fn color(statement: &Statement, path: &Path) -> Result<[f64; 3], LoadError> {
+    if statement.arguments.len() == 1 {
+        let [gray] = statement.numbers(path)?;
+        Ok([gray, gray, gray])
+    } else {
+        statement.numbers(path)
+    }
+}
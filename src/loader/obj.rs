@@ -0,0 +1,425 @@
+use std::{collections::HashMap, io::ErrorKind, path::Path, sync::Arc};
+
+use crate::{
+    hittables::{
+        HittableList,
+        mesh::{MeshData, TriangleMesh},
+    },
+    loader::{self, LoadError, Statement, mtl},
+    materials::Material,
+    vec3::{Direction, Point3},
+};
+
+// One mesh per group or object of the file, further split wherever the material changes.
+pub struct ObjMesh {
+    pub name: String,
+    pub material_name: Option<String>,
+    pub mesh: TriangleMesh,
+}
+
+pub struct ObjModel {
+    pub meshes: Vec<ObjMesh>,
+}
+
+impl ObjModel {
+    pub fn into_hittable_list(self) -> HittableList {
+        let mut list = HittableList::new();
+        for obj_mesh in self.meshes {
+            list.add(Arc::new(obj_mesh.mesh));
+        }
+        list
+    }
+}
+
+// Load an OBJ file, together with any MTL files it names, which are looked for relative to the OBJ file. MTL files that can't be found are skipped. Faces with no `usemtl`, or whose material isn't defined, are given `default_material`.
+pub fn load(
+    path: impl AsRef<Path>,
+    default_material: Arc<dyn Material>,
+) -> Result<ObjModel, LoadError> {
+    let path = path.as_ref();
+    let source = loader::read_to_string(path)?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    let mut materials = HashMap::new();
+    for statement in loader::statements(&source) {
+        if statement.keyword == "mtllib" {
+            for file_name in &statement.arguments {
+                // Models are often passed around without their MTL files. Their faces are then given `default_material`, as are any whose materials aren't defined.
+                let descriptions = match mtl::load(directory.join(file_name)) {
+                    Err(LoadError::Io { source, .. }) if source.kind() == ErrorKind::NotFound => {
+                        continue;
+                    }
+                    result => result?,
+                };
+                materials.extend(mtl::materials(&descriptions));
+            }
+        }
+    }
+
+    parse(&source, path, &materials, default_material)
+}
+
+// Parse the contents of an OBJ file, looking up `usemtl` names in `materials`; `mtllib` statements are ignored. `path` is only used to identify the source in error messages.
+pub fn parse(
+    source: &str,
+    path: &Path,
+    materials: &HashMap<String, Arc<dyn Material>>,
+    default_material: Arc<dyn Material>,
+) -> Result<ObjModel, LoadError> {
+    let mut positions = Vec::new();
+    let mut texcoords = Vec::new();
+    let mut normals = Vec::new();
+
+    let mut meshes = Vec::new();
+    let mut object_name = String::from("default");
+    let mut group_name: Option<String> = None;
+    let mut material_name: Option<String> = None;
+    let mut builder = MeshBuilder::default();
+
+    for statement in loader::statements(source) {
+        match statement.keyword {
+            "v" => {
+                let [x, y, z] = statement.leading_numbers(path)?;
+                positions.push(Point3::new(x, y, z));
+            }
+            "vt" => {
+                let [u, v] = match statement.arguments.len() {
+                    1 => {
+                        let [u] = statement.numbers(path)?;
+                        [u, 0.]
+                    }
+                    _ => statement.leading_numbers(path)?,
+                };
+                texcoords.push([u, v]);
+            }
+            "vn" => {
+                let [x, y, z] = statement.numbers(path)?;
+                normals.push(Direction::new(x, y, z));
+            }
+            "f" => {
+                let attributes = [positions.len(), texcoords.len(), normals.len()];
+                let corners = statement
+                    .arguments
+                    .iter()
+                    .map(|corner| parse_corner(corner, attributes, &statement, path))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if corners.len() < 3 {
+                    return Err(LoadError::parse(
+                        path,
+                        statement.line,
+                        "a face needs at least three vertices",
+                    ));
+                }
+
+                let indices: Vec<usize> = corners
+                    .into_iter()
+                    .map(|corner| builder.vertex(corner, &positions, &texcoords, &normals))
+                    .collect();
+
+                // Triangulate as a fan around the first vertex, which is exact for convex polygons.
+                for i in 1..indices.len() - 1 {
+                    builder
+                        .indices
+                        .push([indices[0], indices[i], indices[i + 1]]);
+                }
+            }
+            "o" | "g" | "usemtl" => {
+                // A bare `g` or `o` goes back to the default group.
+                let name = if statement.keyword != "usemtl" && statement.arguments.is_empty() {
+                    String::from("default")
+                } else {
+                    statement.name(path)?
+                };
+
+                let mesh_name = group_name.clone().unwrap_or_else(|| object_name.clone());
+                if let Some(mesh) = builder.build(
+                    mesh_name,
+                    material_name.clone(),
+                    materials,
+                    &default_material,
+                ) {
+                    meshes.push(mesh);
+                }
+                builder = MeshBuilder::default();
+
+                match statement.keyword {
+                    "o" => {
+                        object_name = name;
+                        group_name = None;
+                    }
+                    "g" => group_name = Some(name),
+                    _ => material_name = Some(name),
+                }
+            }
+            _ => {} // Lines, smoothing groups, free-form geometry etc. aren't supported.
+        }
+    }
+
+    let mesh_name = group_name.unwrap_or(object_name);
+    if let Some(mesh) = builder.build(mesh_name, material_name, materials, &default_material) {
+        meshes.push(mesh);
+    }
+
+    Ok(ObjModel { meshes })
+}
+
+// Indices of the position, texture coordinates and normal of one corner of a face, counting from 0.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+// Corners are written `v`, `v/vt`, `v//vn` or `v/vt/vn`, where each index counts from 1, or backwards from the most recent element if negative.
+fn parse_corner(
+    corner: &str,
+    [position_count, texcoord_count, normal_count]: [usize; 3],
+    statement: &Statement,
+    path: &Path,
+) -> Result<Corner, LoadError> {
+    let mut parts = corner.split('/');
+    let resolve = |part: Option<&str>, count: usize, kind: &str| -> Result<_, LoadError> {
+        let part = match part {
+            None | Some("") => return Ok(None),
+            Some(part) => part,
+        };
+        let error = |message: String| LoadError::parse(path, statement.line, message);
+        let index: i64 = part
+            .parse()
+            .map_err(|_| error(format!("invalid {} index `{}`", kind, part)))?;
+        let resolved = match index {
+            0 => None,
+            i if i > 0 => Some(i as usize - 1),
+            i => count.checked_sub(i.unsigned_abs() as usize),
+        };
+        match resolved {
+            Some(i) if i < count => Ok(Some(i)),
+            _ => Err(error(format!(
+                "{} index {} out of range: {} defined so far",
+                kind, index, count
+            ))),
+        }
+    };
+
+    let position = resolve(parts.next(), position_count, "vertex")?.ok_or_else(|| {
+        LoadError::parse(
+            path,
+            statement.line,
+            format!("missing vertex index in `{}`", corner),
+        )
+    })?;
+    let texcoord = resolve(parts.next(), texcoord_count, "texture coordinate")?;
+    let normal = resolve(parts.next(), normal_count, "normal")?;
+
+    if parts.next().is_some() {
+        return Err(LoadError::parse(
+            path,
+            statement.line,
+            format!("too many indices in `{}`", corner),
+        ));
+    }
+
+    Ok((position, texcoord, normal))
+}
+
+// Collects the vertices of one mesh. A vertex is a distinct combination of position, texture coordinates and normal, so a position shared by faces with different normals (e.g. at the edge of a cube) is duplicated.
+#[derive(Default)]
+struct MeshBuilder {
+    positions: Vec<Point3>,
+    texcoords: Vec<Option<[f64; 2]>>,
+    normals: Vec<Option<Direction>>,
+    indices: Vec<[usize; 3]>,
+    vertices: HashMap<Corner, usize>,
+}
+
+impl MeshBuilder {
+    fn vertex(
+        &mut self,
+        corner: Corner,
+        positions: &[Point3],
+        texcoords: &[[f64; 2]],
+        normals: &[Direction],
+    ) -> usize {
+        *self.vertices.entry(corner).or_insert_with(|| {
+            let (position, texcoord, normal) = corner;
+            self.positions.push(positions[position]);
+            self.texcoords.push(texcoord.map(|i| texcoords[i]));
+            self.normals.push(normal.map(|i| normals[i]));
+            self.positions.len() - 1
+        })
+    }
+
+    fn build(
+        self,
+        name: String,
+        material_name: Option<String>,
+        materials: &HashMap<String, Arc<dyn Material>>,
+        default_material: &Arc<dyn Material>,
+    ) -> Option<ObjMesh> {
+        if self.indices.is_empty() {
+            return None;
+        }
+
+        let material = material_name
+            .as_ref()
+            .and_then(|name| materials.get(name))
+            .unwrap_or(default_material)
+            .clone();
+
+        // Smooth shading and texture mapping are only possible if every vertex has the attribute.
+        let normals = self.normals.into_iter().collect::<Option<Vec<_>>>();
+        let texcoords = self.texcoords.into_iter().collect::<Option<Vec<_>>>();
+
+        let data = MeshData {
+            positions: self.positions,
+            normals,
+            texcoords,
+            indices: self.indices,
+        };
+
+        Some(ObjMesh {
+            name,
+            material_name,
+            mesh: TriangleMesh::new(data, material),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::Color, materials::Lambertian};
+
+    fn default_material() -> Arc<dyn Material> {
+        Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5)))
+    }
+
+    #[test]
+    fn parse_quad_with_normals() {
+        let source = "\
+# A unit square, split into two triangles.
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vn 0 0 1
+f 1//1 2//1 3//1 4//1
+";
+        let model = parse(
+            source,
+            Path::new("square.obj"),
+            &HashMap::new(),
+            default_material(),
+        )
+        .unwrap();
+        assert_eq!(model.meshes.len(), 1);
+        let data = model.meshes[0].mesh.data();
+        assert_eq!(data.positions.len(), 4);
+        assert_eq!(data.indices, vec![[0, 1, 2], [0, 2, 3]]);
+        assert!(data.normals.is_some());
+        assert!(data.texcoords.is_none());
+    }
+
+    #[test]
+    fn groups_and_materials_split_meshes() {
+        let mtl_source = "\
+newmtl red
+Kd 0.8 0.1 0.1
+newmtl lamp
+Ke 4 4 4
+";
+        let descriptions = mtl::parse(mtl_source, Path::new("scene.mtl")).unwrap();
+        assert_eq!(descriptions.len(), 2);
+        assert_eq!(descriptions[1].emission, [4., 4., 4.]);
+        let materials = mtl::materials(&descriptions);
+
+        let source = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 0 0 1
+g first
+usemtl red
+f 1 2 3
+g second
+usemtl lamp
+f -4 -3 -1
+";
+        let model = parse(
+            source,
+            Path::new("scene.obj"),
+            &materials,
+            default_material(),
+        )
+        .unwrap();
+        let names: Vec<_> = model
+            .meshes
+            .iter()
+            .map(|mesh| (mesh.name.as_str(), mesh.material_name.as_deref()))
+            .collect();
+        assert_eq!(names, [("first", Some("red")), ("second", Some("lamp"))]);
+    }
+
+    #[test]
+    fn bare_groups_and_unknown_materials_fall_back_to_defaults() {
+        let source = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+g wing
+usemtl missing
+f 1 2 3
+g
+f 3 2 1
+";
+        let model = parse(
+            source,
+            Path::new("partial.obj"),
+            &HashMap::new(),
+            default_material(),
+        )
+        .unwrap();
+        let names: Vec<_> = model
+            .meshes
+            .iter()
+            .map(|mesh| (mesh.name.as_str(), mesh.material_name.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            [("wing", Some("missing")), ("default", Some("missing"))]
+        );
+    }
+
+    #[test]
+    fn errors_report_line_numbers() {
+        let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\n";
+        let error = parse(
+            source,
+            Path::new("bad.obj"),
+            &HashMap::new(),
+            default_material(),
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(error, LoadError::Parse { line: 4, .. }));
+
+        let source = "newmtl glass\nNi zero\n";
+        let error = mtl::parse(source, Path::new("bad.mtl")).err().unwrap();
+        assert!(matches!(error, LoadError::Parse { line: 2, .. }));
+    }
+
+    #[test]
+    fn missing_file_is_an_io_error() {
+        let error = load("no/such/file.obj", default_material()).err().unwrap();
+        assert!(matches!(error, LoadError::Io { .. }));
+    }
+
+    #[test]
+    fn missing_mtl_files_are_skipped() {
+        let path = std::env::temp_dir().join("rt_missing_mtl.obj");
+        std::fs::write(
+            &path,
+            "mtllib no_such.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n",
+        )
+        .unwrap();
+        let model = load(&path, default_material());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(model.unwrap().meshes.len(), 1);
+    }
+}
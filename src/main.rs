@@ -9,6 +9,9 @@ fn main() -> io::Result<()> {
     // // The cover illustration of Ray Tracing in One Weekend, which is bigger than the others and so takes longer. The authors use 500 sampes per pixel for a high-quality image, which will take, as they warn, "quite a while".
     // examples::book::random_spheres::render(50, 500)?;
 
+    // // The same, with the small diffuse spheres bouncing while the shutter is open, from the start of Ray Tracing: The Next Week.
+    // examples::book::bouncing_spheres::render(50, 100)?;
+
     // // Some demos I made.
     // examples::demo::basic::render(10, 500, 800)?;
     // examples::demo::combo::render(10, 500, 800)?;
@@ -16,6 +19,9 @@ fn main() -> io::Result<()> {
     // examples::demo::sunset::render(10, 500, 800)?;
     // examples::demo::this_floating_world::render(10, 500, 800)?;
     // examples::demo::balloons::render(10, 500, 800)?;
+    // examples::demo::landscape::render(10, 500, 800)?;
+    // examples::demo::metals::render(10, 500, 800)?;
+    // examples::demo::prism::render(20, 500, 800)?;
 
     // These are the scenes I created to meet the given requirements of the 01Founders project.
     let max_depth = 50;
@@ -1,32 +1,73 @@
+pub mod conductor;
+pub mod microfacet;
+pub mod thin_film;
+
+use std::{f64::consts::PI, sync::Arc};
+
 use rand::{Rng, rngs::SmallRng};
 
 use crate::{
     color::Color,
+    hittables::HitRecord,
+    materials::microfacet::{Ggx, GgxInterface},
+    pdf::{CosinePdf, Pdf, SpherePdf},
     ray::Ray,
-    vec3::{Direction, Point3},
+    spectrum::{self, Dispersion},
+    textures::{SolidColor, Texture},
+    vec3::Direction,
 };
 
+// Where light goes when it meets a surface.
+pub enum ScatterRecord {
+    // A single direction, or one picked at random by the material itself, as from a mirror or glass, with the fraction of each color that's passed on. The renderer just follows it.
+    Specular { ray: Ray, attenuation: Color },
+    // Spread over many directions, as from a matt surface. `pdf` chooses directions roughly in proportion to how much light goes each way. The renderer is free to choose directions some other way too, e.g. by aiming at a light, and asks the material's `eval` how much light goes whichever way it chose.
+    Diffuse { pdf: Box<dyn Pdf> },
+}
+
 pub trait Material: Send + Sync {
     fn scatter(
         &self,
         incident_ray: &Ray,
-        point: &Point3,
-        normal: &Direction,
-        front_face: bool,
+        record: &HitRecord,
         rngs: &mut SmallRng,
-    ) -> Option<(Ray, Color)>;
+    ) -> Option<ScatterRecord>;
+
+    // For materials that scatter diffusely: the fraction of each color of the light arriving along `incident_ray` that leaves in `direction`, per unit solid angle, including the cosine factor for the angle with the normal. That is, the BSDF times the cosine. Light arriving in a pencil of solid angle dω is passed on as `eval` · dω.
+    fn eval(&self, _incident_ray: &Ray, _record: &HitRecord, _direction: &Direction) -> Color {
+        Color::new(0., 0., 0.)
+    }
 
-    fn emit(&self, _point: &Point3) -> Color {
+    fn emit(&self, _record: &HitRecord) -> Color {
         Color::new(0., 0., 0.)
     }
+
+    // Whether the directions the material sends light in depend on its wavelength, as with glass that splits white light into colors. In spectral rendering, only the hero wavelength is followed on from such a material.
+    fn is_dispersive(&self) -> bool {
+        false
+    }
+
+    // Whether the attenuations `scatter` returns in spectral rendering are already the fractions passed on at the three wavelengths of `incident_ray`, rather than colors for the renderer to turn into a spectrum. For materials whose color comes from how they treat each wavelength, like a soap film.
+    fn is_spectral(&self) -> bool {
+        false
+    }
+
+    // For materials that light can pass into, like glass: the fraction of each color absorbed per unit distance travelled inside, i.e. zero for clear materials. `None` for materials with no inside to speak of.
+    fn absorption(&self) -> Option<Color> {
+        None
+    }
 }
 
 pub struct Lambertian {
-    pub albedo: Color,
+    pub albedo: Arc<dyn Texture>,
 }
 
 impl Lambertian {
     pub fn new(albedo: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>) -> Self {
         Lambertian { albedo }
     }
 }
@@ -35,28 +76,35 @@ impl Material for Lambertian {
     fn scatter(
         &self,
         _incident_ray: &Ray,
-        point: &Point3,
-        normal: &Direction,
-        _front_face: bool,
-        rng: &mut SmallRng,
-    ) -> Option<(Ray, Color)> {
-        let mut scatter_direction = *normal + Direction::random_unit(rng);
-        if scatter_direction.near_zero() {
-            scatter_direction = normal.clone();
+        record: &HitRecord,
+        _rng: &mut SmallRng,
+    ) -> Option<ScatterRecord> {
+        Some(ScatterRecord::Diffuse {
+            pdf: Box::new(CosinePdf::new(&record.normal)),
+        })
+    }
+
+    fn eval(&self, _incident_ray: &Ray, record: &HitRecord, direction: &Direction) -> Color {
+        let cosine = record.normal.dot(&direction.normalize());
+        if cosine <= 0. {
+            return Color::new(0., 0., 0.);
         }
-        let scattered = Ray::new(point.clone(), scatter_direction);
-        let attenuation = self.albedo.clone();
-        Some((scattered, attenuation))
+        self.albedo.value(record.u, record.v, &record.point) * cosine / PI
     }
 }
 
+// The book's metal: a mirror whose reflections are nudged in a random direction, further the fuzzier it is. That's quick and looks plausible, but it isn't physically based; `conductor::Conductor` is. `Metal` stays so that the book's examples come out as they do in the book.
 pub struct Metal {
-    pub albedo: Color,
+    pub albedo: Arc<dyn Texture>,
     pub fuzz: f64, // in the range [0.0, 1.0]
 }
 
 impl Metal {
     pub fn new(albedo: Color, fuzz: f64) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)), fuzz)
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>, fuzz: f64) -> Self {
         Metal {
             albedo,
             fuzz: fuzz.clamp(0., 1.),
@@ -68,30 +116,84 @@ impl Material for Metal {
     fn scatter(
         &self,
         incident_ray: &Ray,
-        point: &Point3,
-        normal: &Direction,
-        _front_face: bool,
+        record: &HitRecord,
         rng: &mut SmallRng,
-    ) -> Option<(Ray, Color)> {
-        let mut reflected = incident_ray.direction.reflect(normal);
+    ) -> Option<ScatterRecord> {
+        let mut reflected = incident_ray.direction.reflect(&record.normal);
         reflected = reflected.normalize() + self.fuzz * Direction::random_unit(rng);
         if reflected.near_zero() {
-            reflected = normal.clone();
+            reflected = record.normal;
         }
-        let scattered = Ray::new(point.clone(), reflected);
-        let attenuation = self.albedo.clone();
-        Some((scattered, attenuation))
+        let scattered = Ray::new(record.point, reflected).with_time(incident_ray.time);
+        Some(ScatterRecord::Specular {
+            ray: scattered,
+            attenuation: self.albedo.value(record.u, record.v, &record.point),
+        })
     }
 }
 
 pub struct Dielectric {
     pub refraction_index: f64,
+    ggx: Ggx,
+    absorption: Color,
+    dispersion: Option<Dispersion>,
 }
 
 impl Dielectric {
     pub fn new(refraction_index: f64) -> Self {
         assert!(1e-8 < refraction_index, "Refraction index is too small");
-        Dielectric { refraction_index }
+        Dielectric {
+            refraction_index,
+            ggx: Ggx::from_roughness(0.),
+            absorption: Color::new(0., 0., 0.),
+            dispersion: None,
+        }
+    }
+
+    // A refractive index that varies with wavelength, such as `Dispersion::bk7()`, in place of the one given to `new`, which is replaced by its value for yellow light. With `Camera::with_spectral_rendering`, this splits white light into colors, as in a prism or a diamond. The index is relative, like the one given to `new`: it's the material's own if the material is surrounded by air.
+    pub fn with_dispersion(mut self, dispersion: Dispersion) -> Self {
+        self.refraction_index = dispersion.refraction_index(spectrum::D_LINE);
+        self.dispersion = Some(dispersion);
+        self
+    }
+
+    // Coloured glass: light travelling a distance d inside is dimmed by a factor of exp(-absorption · d) for each color, so thick parts of an object look deeper in colour than thin parts. Higher values absorb faster; 0.0 absorbs nothing.
+    pub fn with_absorption(mut self, absorption: Color) -> Self {
+        assert!(
+            absorption.r >= 0. && absorption.g >= 0. && absorption.b >= 0.,
+            "Absorption can't be negative"
+        );
+        self.absorption = absorption;
+        self
+    }
+
+    // The absorption that leaves `color` after light has travelled `distance` inside, which is often easier to choose.
+    pub fn with_color_at_distance(self, color: Color, distance: f64) -> Self {
+        assert!(1e-8 < distance, "Distance is too small");
+        let coefficient = |fraction: f64| -fraction.clamp(1e-8, 1.).ln() / distance;
+        self.with_absorption(Color::new(
+            coefficient(color.r),
+            coefficient(color.g),
+            coefficient(color.b),
+        ))
+    }
+
+    // Frosted or etched, rather than polished: `roughness` is in the range [0.0, 1.0], as for `Conductor`. Light is reflected and refracted in a spread of directions around those it would take at a smooth surface.
+    pub fn with_roughness(mut self, roughness: f64) -> Self {
+        self.ggx = Ggx::from_roughness(roughness);
+        self
+    }
+
+    fn relative_refraction_index(&self, incident_ray: &Ray, record: &HitRecord) -> f64 {
+        let refraction_index = match (self.dispersion, incident_ray.wavelength) {
+            (Some(dispersion), Some(wavelength)) => dispersion.refraction_index(wavelength),
+            _ => self.refraction_index,
+        };
+        if record.front_face {
+            refraction_index
+        } else {
+            1. / refraction_index
+        }
     }
 }
 
@@ -99,19 +201,22 @@ impl Material for Dielectric {
     fn scatter(
         &self,
         incident_ray: &Ray,
-        point: &Point3,
-        normal: &Direction,
-        front_face: bool,
+        record: &HitRecord,
         rng: &mut SmallRng,
-    ) -> Option<(Ray, Color)> {
-        let attenuation = Color::new(1., 1., 1.);
+    ) -> Option<ScatterRecord> {
+        let refraction_index = self.relative_refraction_index(incident_ray, record);
+        if !self.ggx.is_smooth() {
+            let outgoing = -incident_ray.direction;
+            let interface =
+                GgxInterface::new(&record.normal, &outgoing, self.ggx, refraction_index);
+            return Some(ScatterRecord::Diffuse {
+                pdf: Box::new(interface),
+            });
+        }
 
-        let refraction_index = if front_face {
-            self.refraction_index
-        } else {
-            1. / self.refraction_index
-        };
+        let attenuation = Color::new(1., 1., 1.);
 
+        let normal = &record.normal;
         let unit_direction = incident_ray.direction.normalize();
         let cos_theta = -unit_direction.dot(normal).min(1.);
         let sin_theta = (1. - cos_theta * cos_theta).sqrt();
@@ -128,13 +233,32 @@ impl Material for Dielectric {
         if direction.near_zero() {
             direction = *normal;
         }
-        let scattered = Ray::new(*point, direction);
-        Some((scattered, attenuation))
+        let scattered = Ray::new(record.point, direction).with_time(incident_ray.time);
+        Some(ScatterRecord::Specular {
+            ray: scattered,
+            attenuation,
+        })
+    }
+
+    fn eval(&self, incident_ray: &Ray, record: &HitRecord, direction: &Direction) -> Color {
+        let outgoing = -incident_ray.direction;
+        let refraction_index = self.relative_refraction_index(incident_ray, record);
+        let interface = GgxInterface::new(&record.normal, &outgoing, self.ggx, refraction_index);
+        let value = interface.eval(direction);
+        Color::new(value, value, value)
+    }
+
+    fn is_dispersive(&self) -> bool {
+        self.dispersion.is_some()
+    }
+
+    fn absorption(&self) -> Option<Color> {
+        Some(self.absorption.clone())
     }
 }
 
 impl Dielectric {
-    fn reflectance(cosine: f64, refraction_index: f64) -> f64 {
+    pub fn reflectance(cosine: f64, refraction_index: f64) -> f64 {
         // Schick approximation
         let mut r_0 = (1. - refraction_index) / (1. + refraction_index);
         r_0 *= r_0;
@@ -143,38 +267,44 @@ impl Dielectric {
 }
 
 pub struct Light {
-    pub color: Color,
+    pub color: Arc<dyn Texture>,
 }
 
 impl Material for Light {
     fn scatter(
         &self,
         _incident_ray: &Ray,
-        _point: &Point3,
-        _normal: &Direction,
-        _front_face: bool,
+        _record: &HitRecord,
         _rng: &mut SmallRng,
-    ) -> Option<(Ray, Color)> {
+    ) -> Option<ScatterRecord> {
         None
     }
 
-    fn emit(&self, _point: &Point3) -> Color {
-        self.color.clone()
+    fn emit(&self, record: &HitRecord) -> Color {
+        self.color.value(record.u, record.v, &record.point)
     }
 }
 
 impl Light {
     pub fn new(color: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(color)))
+    }
+
+    pub fn from_texture(color: Arc<dyn Texture>) -> Self {
         Light { color }
     }
 }
 
 pub struct Isotropic {
-    albedo: Color,
+    albedo: Arc<dyn Texture>,
 }
 
 impl Isotropic {
     pub fn new(albedo: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>) -> Self {
         Self { albedo }
     }
 }
@@ -183,12 +313,15 @@ impl Material for Isotropic {
     fn scatter(
         &self,
         _incident_ray: &Ray,
-        point: &Point3,
-        _normal: &Direction,
-        _front_face: bool,
-        rng: &mut SmallRng,
-    ) -> Option<(Ray, Color)> {
-        let scattered = Ray::new(*point, Direction::random_unit(rng));
-        Some((scattered, self.albedo.clone()))
+        _record: &HitRecord,
+        _rng: &mut SmallRng,
+    ) -> Option<ScatterRecord> {
+        Some(ScatterRecord::Diffuse {
+            pdf: Box::new(SpherePdf),
+        })
+    }
+
+    fn eval(&self, _incident_ray: &Ray, record: &HitRecord, _direction: &Direction) -> Color {
+        self.albedo.value(record.u, record.v, &record.point) / (4. * PI)
     }
 }
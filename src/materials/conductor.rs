@@ -0,0 +1,202 @@
+use rand::rngs::SmallRng;
+
+use crate::{
+    color::Color,
+    hittables::HitRecord,
+    materials::{
+        Material, ScatterRecord,
+        microfacet::{self, Ggx, GgxReflectionPdf},
+    },
+    ray::Ray,
+    vec3::Direction,
+};
+
+// How much of the light arriving at a given angle a metal reflects.
+#[derive(Clone)]
+pub enum Fresnel {
+    // Schlick's approximation: the color seen head on, rising towards white at grazing angles.
+    Schlick(Color),
+    // The exact answer for a metal with complex refractive index n + ik, given separately for red, green and blue.
+    Complex { n: Color, k: Color },
+}
+
+impl Fresnel {
+    fn reflectance(&self, cosine: f64) -> Color {
+        let cosine = cosine.clamp(0., 1.);
+        match self {
+            Fresnel::Schlick(color) => {
+                let grazing = (1. - cosine).powi(5);
+                color.clone() + (Color::new(1., 1., 1.) - color.clone()) * grazing
+            }
+            Fresnel::Complex { n, k } => Color::new(
+                conductor_reflectance(cosine, n.r, k.r),
+                conductor_reflectance(cosine, n.g, k.g),
+                conductor_reflectance(cosine, n.b, k.b),
+            ),
+        }
+    }
+}
+
+// The average of the reflectances for light polarized parallel and perpendicular to the plane of incidence.
+fn conductor_reflectance(cosine: f64, n: f64, k: f64) -> f64 {
+    let cos_2 = cosine * cosine;
+    let sin_2 = 1. - cos_2;
+    let (n_2, k_2) = (n * n, k * k);
+    let t_0 = n_2 - k_2 - sin_2;
+    let a_2_plus_b_2 = (t_0 * t_0 + 4. * n_2 * k_2).sqrt();
+    let a = (0.5 * (a_2_plus_b_2 + t_0)).max(0.).sqrt();
+    let t_1 = a_2_plus_b_2 + cos_2;
+    let t_2 = 2. * cosine * a;
+    let perpendicular = (t_1 - t_2) / (t_1 + t_2);
+    let t_3 = cos_2 * a_2_plus_b_2 + sin_2 * sin_2;
+    let t_4 = t_2 * sin_2;
+    let parallel = perpendicular * (t_3 - t_4) / (t_3 + t_4);
+    0.5 * (perpendicular + parallel)
+}
+
+// A metal, smooth or rough. Unlike `Metal`, it's physically based: rough surfaces are modeled as countless tiny mirrors with a GGX distribution of normals, some hidden from view or in the shadow of others, and the reflectance depends on the angle as for real metals. So no light is scattered into the surface, and none is created.
+pub struct Conductor {
+    fresnel: Fresnel,
+    ggx: Ggx,
+}
+
+impl Conductor {
+    // `color` is the color reflected head on. `roughness` is in the range [0.0, 1.0]: 0.0 for a perfect mirror.
+    pub fn new(color: Color, roughness: f64) -> Self {
+        Self::from_fresnel(Fresnel::Schlick(color), roughness)
+    }
+
+    // From measured complex refractive indices for red, green and blue light.
+    pub fn from_complex_ior(n: Color, k: Color, roughness: f64) -> Self {
+        Self::from_fresnel(Fresnel::Complex { n, k }, roughness)
+    }
+
+    pub fn from_fresnel(fresnel: Fresnel, roughness: f64) -> Self {
+        Self {
+            fresnel,
+            ggx: Ggx::from_roughness(roughness),
+        }
+    }
+
+    // Refractive indices at about 650, 550 and 450 nm.
+    pub fn gold(roughness: f64) -> Self {
+        Self::from_complex_ior(
+            Color::new(0.143, 0.374, 1.442),
+            Color::new(3.983, 2.385, 1.603),
+            roughness,
+        )
+    }
+
+    pub fn copper(roughness: f64) -> Self {
+        Self::from_complex_ior(
+            Color::new(0.200, 0.924, 1.102),
+            Color::new(3.912, 2.452, 2.142),
+            roughness,
+        )
+    }
+
+    pub fn aluminium(roughness: f64) -> Self {
+        Self::from_complex_ior(
+            Color::new(1.657, 0.880, 0.521),
+            Color::new(9.224, 6.270, 4.837),
+            roughness,
+        )
+    }
+
+    pub fn silver(roughness: f64) -> Self {
+        Self::from_complex_ior(
+            Color::new(0.155, 0.117, 0.138),
+            Color::new(4.828, 3.122, 2.147),
+            roughness,
+        )
+    }
+}
+
+impl Material for Conductor {
+    fn scatter(
+        &self,
+        incident_ray: &Ray,
+        record: &HitRecord,
+        _rng: &mut SmallRng,
+    ) -> Option<ScatterRecord> {
+        let outgoing = -incident_ray.direction.normalize();
+        if self.ggx.is_smooth() {
+            let reflected = incident_ray.direction.reflect(&record.normal);
+            let cosine = outgoing.dot(&record.normal);
+            return Some(ScatterRecord::Specular {
+                ray: Ray::new(record.point, reflected).with_time(incident_ray.time),
+                attenuation: self.fresnel.reflectance(cosine),
+            });
+        }
+        Some(ScatterRecord::Diffuse {
+            pdf: Box::new(GgxReflectionPdf::new(&record.normal, &outgoing, self.ggx)),
+        })
+    }
+
+    // The Cook-Torrance microfacet BRDF, times the cosine: F D G / (4 cos_o cos_i) · cos_i.
+    fn eval(&self, incident_ray: &Ray, record: &HitRecord, direction: &Direction) -> Color {
+        let black = Color::new(0., 0., 0.);
+        let frame = microfacet::local_frame(&record.normal);
+        let outgoing = microfacet::to_local(&frame, &-incident_ray.direction.normalize());
+        let incoming = microfacet::to_local(&frame, &direction.normalize());
+        if outgoing.z <= 0. || incoming.z <= 0. {
+            return black;
+        }
+        let h = (outgoing + incoming).normalize();
+        let d = self.ggx.distribution(&h);
+        let g = self.ggx.masking_shadowing(&outgoing, &incoming);
+        self.fresnel.reflectance(outgoing.dot(&h)) * (d * g / (4. * outgoing.z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{materials::Lambertian, vec3::Point3};
+
+    #[test]
+    fn conductors_reflect_without_gaining_energy() {
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        // With no absorption, the exact formula agrees with the familiar one for glass at normal incidence.
+        let glass = conductor_reflectance(1., 1.5, 0.);
+        assert!((glass - 0.04).abs() < 1e-9);
+        // Metals reflect most light, and all of it at grazing angles.
+        let gold = Fresnel::Complex {
+            n: Color::new(0.143, 0.374, 1.442),
+            k: Color::new(3.983, 2.385, 1.603),
+        };
+        assert!(gold.reflectance(1.).r > 0.9 && gold.reflectance(1.).b < 0.5);
+        assert!((gold.reflectance(0.).b - 1.).abs() < 1e-9);
+
+        // A white conductor returns at most the light it receives, and nearly all of it when it's only slightly rough. Rougher ones lose more to light that would reach the camera after bouncing between facets, which isn't modeled.
+        let normal = Direction::new(0., 1., 0.);
+        let surface = Arc::new(Lambertian::new(Color::new(1., 1., 1.)));
+        let ray = Ray::new(Point3::new(-1., 1., 0.), Direction::new(1., -1., 0.));
+        let record = HitRecord::new(Point3::new(0., 0., 0.), normal, 1., surface, &ray);
+        for (roughness, minimum) in [(0.2, 0.98), (0.6, 0.75), (1., 0.3)] {
+            let conductor = Conductor::new(Color::new(1., 1., 1.), roughness);
+            let Some(ScatterRecord::Diffuse { pdf }) = conductor.scatter(&ray, &record, &mut rng)
+            else {
+                panic!("Expected a rough conductor to scatter diffusely");
+            };
+            let samples = 20_000;
+            let mut total = 0.;
+            for _ in 0..samples {
+                let direction = pdf.generate(&mut rng);
+                let density = pdf.value(&direction, &mut rng);
+                if density > 0. {
+                    let weight = conductor.eval(&ray, &record, &direction).r / density;
+                    assert!(weight <= 1. + 1e-9, "Gained energy: {weight}");
+                    total += weight;
+                }
+            }
+            let albedo = total / samples as f64;
+            assert!(minimum < albedo && albedo <= 1., "{roughness}: {albedo}");
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use std::f64::consts::PI;
+
+use rand::{Rng, rngs::SmallRng};
+
+use crate::{
+    materials::Dielectric,
+    pdf::Pdf,
+    vec3::{self, Basis, Direction},
+};
+
+// A rough surface modeled as countless tiny mirrors (microfacets), with their normals spread out according to the GGX, or Trowbridge-Reitz, distribution. Directions are in a local frame, with the z axis along the surface normal.
+#[derive(Clone, Copy)]
+pub struct Ggx {
+    pub alpha: f64,
+}
+
+impl Ggx {
+    // `roughness` is in the range [0.0, 1.0]. It's squared to give the width of the distribution, which makes equal steps in roughness look roughly equally different.
+    pub fn from_roughness(roughness: f64) -> Self {
+        let roughness = roughness.clamp(0., 1.);
+        Self {
+            alpha: (roughness * roughness).max(1e-4),
+        }
+    }
+
+    // Below this, the surface is treated as a perfect mirror, since the distribution is too sharp to sample or evaluate reliably.
+    pub fn is_smooth(&self) -> bool {
+        self.alpha < 1e-3
+    }
+
+    // The density of microfacet normals `h`, per unit solid angle, projected onto the surface: it integrates to 1 when multiplied by h.z.
+    pub fn distribution(&self, h: &Direction) -> f64 {
+        if h.z <= 0. {
+            return 0.;
+        }
+        let alpha_2 = self.alpha * self.alpha;
+        let d = h.z * h.z * (alpha_2 - 1.) + 1.;
+        alpha_2 / (PI * d * d)
+    }
+
+    // Smith's auxiliary function: roughly, the fraction of microfacets hidden behind others when seen from `v`.
+    fn lambda(&self, v: &Direction) -> f64 {
+        let cos_2 = v.z * v.z;
+        if cos_2 <= 0. {
+            return f64::INFINITY;
+        }
+        let tan_2 = (1. - cos_2).max(0.) / cos_2;
+        ((1. + self.alpha * self.alpha * tan_2).sqrt() - 1.) / 2.
+    }
+
+    // The fraction of microfacets visible from `v`.
+    pub fn masking(&self, v: &Direction) -> f64 {
+        1. / (1. + self.lambda(v))
+    }
+
+    // The fraction visible from both `v` and `w`, taking account of the fact that a facet high up enough to be seen from one is more likely to be seen from the other too.
+    pub fn masking_shadowing(&self, v: &Direction, w: &Direction) -> f64 {
+        1. / (1. + self.lambda(v) + self.lambda(w))
+    }
+
+    // A microfacet normal chosen in proportion to how much of it is visible from `v`: its area, as seen from `v`, of the facets that face `v`. Unlike sampling the distribution itself, this never wastes samples on facets that can't be seen (Heitz, 2018).
+    pub fn sample_visible_normal(&self, v: &Direction, rng: &mut SmallRng) -> Direction {
+        // Stretch the surface so that the microfacets form a hemisphere, pick a point on the part of it seen from `v`, then squash it back.
+        let stretched = Direction::new(self.alpha * v.x, self.alpha * v.y, v.z).normalize();
+        let length_2 = stretched.x * stretched.x + stretched.y * stretched.y;
+        let t_1 = if length_2 > 0. {
+            Direction::new(-stretched.y, stretched.x, 0.) / length_2.sqrt()
+        } else {
+            Direction::new(1., 0., 0.)
+        };
+        let t_2 = stretched.cross(&t_1);
+
+        let r = rng.random::<f64>().sqrt();
+        let phi = 2. * PI * rng.random::<f64>();
+        let p_1 = r * phi.cos();
+        let s = 0.5 * (1. + stretched.z);
+        let p_2 = (1. - s) * (1. - p_1 * p_1).sqrt() + s * r * phi.sin();
+        let p_3 = (1. - p_1 * p_1 - p_2 * p_2).max(0.).sqrt();
+        let h = p_1 * t_1 + p_2 * t_2 + p_3 * stretched;
+
+        Direction::new(self.alpha * h.x, self.alpha * h.y, h.z.max(0.)).normalize()
+    }
+
+    // The density, per unit solid angle, of choosing the microfacet normal `h` with `sample_visible_normal`.
+    pub fn visible_normal_pdf(&self, v: &Direction, h: &Direction) -> f64 {
+        if v.z <= 0. {
+            return 0.;
+        }
+        self.masking(v) * v.dot(h).max(0.) * self.distribution(h) / v.z
+    }
+}
+
+// A frame with its z axis along `normal`, for turning directions into the local ones that `Ggx` works with, and back.
+pub fn local_frame(normal: &Direction) -> Basis {
+    let [u, v] = vec3::orthonormal_basis_2d(normal);
+    Basis {
+        x: u,
+        y: v,
+        z: normal.normalize(),
+    }
+}
+
+pub fn to_local(frame: &Basis, direction: &Direction) -> Direction {
+    Direction::new(
+        direction.dot(&frame.x),
+        direction.dot(&frame.y),
+        direction.dot(&frame.z),
+    )
+}
+
+pub fn to_world(frame: &Basis, direction: &Direction) -> Direction {
+    direction.x * frame.x + direction.y * frame.y + direction.z * frame.z
+}
+
+// Directions of light reflected off a rough surface towards `outgoing`, chosen by picking a visible microfacet and reflecting off it.
+pub struct GgxReflectionPdf {
+    frame: Basis,
+    outgoing: Direction, // in the local frame
+    ggx: Ggx,
+}
+
+impl GgxReflectionPdf {
+    pub fn new(normal: &Direction, outgoing: &Direction, ggx: Ggx) -> Self {
+        let frame = local_frame(normal);
+        let outgoing = to_local(&frame, &outgoing.normalize());
+        Self {
+            frame,
+            outgoing,
+            ggx,
+        }
+    }
+}
+
+impl Pdf for GgxReflectionPdf {
+    fn value(&self, direction: &Direction, _rng: &mut SmallRng) -> f64 {
+        let incoming = to_local(&self.frame, &direction.normalize());
+        if incoming.z <= 0. || self.outgoing.z <= 0. {
+            return 0.;
+        }
+        let h = (incoming + self.outgoing).normalize();
+        // Reflection doubles the angle, which spreads the directions out over four times the solid angle the normals cover, at the rate given by this Jacobian.
+        self.ggx.visible_normal_pdf(&self.outgoing, &h) / (4. * self.outgoing.dot(&h))
+    }
+
+    fn generate(&self, rng: &mut SmallRng) -> Direction {
+        let h = self.ggx.sample_visible_normal(&self.outgoing, rng);
+        let incoming = (-self.outgoing).reflect(&h);
+        to_world(&self.frame, &incoming)
+    }
+}
+
+// Light scattered by a rough boundary between two clear materials, as seen from `outgoing`: some of it reflected, the rest refracted through, off microfacets chosen as for `GgxReflectionPdf` (Walter et al., 2007). `refraction_index` is as for the smooth `Dielectric`: the ratio passed to `refract` for a ray crossing from the near side to the far side.
+pub struct GgxInterface {
+    frame: Basis,
+    outgoing: Direction, // in the local frame
+    ggx: Ggx,
+    refraction_index: f64,
+}
+
+impl GgxInterface {
+    pub fn new(normal: &Direction, outgoing: &Direction, ggx: Ggx, refraction_index: f64) -> Self {
+        let frame = local_frame(normal);
+        let outgoing = to_local(&frame, &outgoing.normalize());
+        Self {
+            frame,
+            outgoing,
+            ggx,
+            refraction_index,
+        }
+    }
+
+    // The fraction of light reflected by a microfacet at this angle: all of it, past the critical angle.
+    fn fresnel(&self, cosine: f64) -> f64 {
+        let sine = (1. - cosine * cosine).max(0.).sqrt();
+        if self.refraction_index * sine > 1. {
+            return 1.;
+        }
+        Dielectric::reflectance(cosine, self.refraction_index)
+    }
+
+    // The microfacet normal that would send light between `outgoing` and `incoming`, whether by reflection or refraction, with, for refraction, the denominator of the Jacobian between them. None if no microfacet facing the viewer can.
+    fn half_vector(&self, incoming: &Direction) -> Option<(Direction, Option<f64>)> {
+        let outgoing = &self.outgoing;
+        if outgoing.z <= 0. || incoming.z == 0. {
+            return None;
+        }
+        if incoming.z > 0. {
+            let h = (*outgoing + *incoming).normalize();
+            return (outgoing.dot(&h) > 0.).then_some((h, None));
+        }
+        // Snell's law says that the components along the surface of the microfacet cancel out when each direction is weighted by the refractive index on its side.
+        let eta = 1. / self.refraction_index;
+        let mut h = -(*outgoing + eta * *incoming);
+        if h.near_zero() {
+            return None;
+        }
+        h = h.normalize();
+        if h.z < 0. {
+            h = -h;
+        }
+        let (o_dot_h, i_dot_h) = (outgoing.dot(&h), incoming.dot(&h));
+        if o_dot_h <= 0. || i_dot_h >= 0. {
+            return None;
+        }
+        let denominator = o_dot_h + eta * i_dot_h;
+        Some((h, Some(denominator * denominator)))
+    }
+
+    // The BSDF times the cosine, as for `Material::eval`. Refraction doesn't scale by the square of the refractive index, as physically it should for radiance, so that this agrees with the smooth `Dielectric`, which doesn't either.
+    pub fn eval(&self, direction: &Direction) -> f64 {
+        let incoming = to_local(&self.frame, &direction.normalize());
+        let Some((h, jacobian_denominator)) = self.half_vector(&incoming) else {
+            return 0.;
+        };
+        let outgoing = &self.outgoing;
+        let o_dot_h = outgoing.dot(&h);
+        let d = self.ggx.distribution(&h);
+        let g = self.ggx.masking_shadowing(outgoing, &incoming);
+        let fresnel = self.fresnel(o_dot_h);
+        match jacobian_denominator {
+            None => fresnel * d * g / (4. * outgoing.z),
+            Some(denominator) => {
+                let eta = 1. / self.refraction_index;
+                let i_dot_h = incoming.dot(&h).abs();
+                o_dot_h * i_dot_h * eta * eta * (1. - fresnel) * g * d / (outgoing.z * denominator)
+            }
+        }
+    }
+}
+
+impl Pdf for GgxInterface {
+    fn value(&self, direction: &Direction, _rng: &mut SmallRng) -> f64 {
+        let incoming = to_local(&self.frame, &direction.normalize());
+        let Some((h, jacobian_denominator)) = self.half_vector(&incoming) else {
+            return 0.;
+        };
+        let o_dot_h = self.outgoing.dot(&h);
+        let visible = self.ggx.visible_normal_pdf(&self.outgoing, &h);
+        let fresnel = self.fresnel(o_dot_h);
+        match jacobian_denominator {
+            None => fresnel * visible / (4. * o_dot_h),
+            Some(denominator) => {
+                let eta = 1. / self.refraction_index;
+                (1. - fresnel) * visible * eta * eta * incoming.dot(&h).abs() / denominator
+            }
+        }
+    }
+
+    fn generate(&self, rng: &mut SmallRng) -> Direction {
+        let h = self.ggx.sample_visible_normal(&self.outgoing, rng);
+        let incident = -self.outgoing;
+        let incoming = if self.fresnel(self.outgoing.dot(&h)) > rng.random::<f64>() {
+            incident.reflect(&h)
+        } else {
+            incident.refract(&h, self.refraction_index)
+        };
+        to_world(&self.frame, &incoming)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn visible_normals_are_sampled_in_proportion_to_their_density() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let v = Direction::new(0.6, 0., 0.8);
+        let samples = 200_000;
+        for roughness in [0.5, 0.8, 1.] {
+            let ggx = Ggx::from_roughness(roughness);
+
+            // Estimated from directions spread evenly over the sphere: the projected distribution covers the surface exactly once, the density of visible normals adds up to 1, and their average leans towards `v`.
+            let (mut projected_area, mut total, mut mean_x) = (0., 0., 0.);
+            for _ in 0..samples {
+                let h = Direction::random_unit(&mut rng);
+                projected_area += ggx.distribution(&h) * h.z.max(0.) * 4. * PI;
+                let density = ggx.visible_normal_pdf(&v, &h) * 4. * PI;
+                total += density;
+                mean_x += density * h.x;
+            }
+            let [projected_area, total, mean_x] =
+                [projected_area, total, mean_x].map(|sum| sum / samples as f64);
+            assert!((projected_area - 1.).abs() < 0.03, "{projected_area}");
+            assert!((total - 1.).abs() < 0.03, "{total}");
+
+            // The sampled normals agree.
+            let mut sampled_mean_x = 0.;
+            for _ in 0..samples {
+                let h = ggx.sample_visible_normal(&v, &mut rng);
+                assert!(v.dot(&h) >= -1e-9 && h.z >= 0., "Sampled a hidden normal");
+                sampled_mean_x += h.x;
+            }
+            sampled_mean_x /= samples as f64;
+            assert!(
+                (mean_x - sampled_mean_x).abs() < 0.02,
+                "{mean_x} {sampled_mean_x}"
+            );
+        }
+    }
+
+    #[test]
+    fn rough_interfaces_conserve_energy() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let normal = Direction::new(0., 0., 1.);
+        let samples = 20_000;
+        for outgoing in [Direction::new(0., 0., 1.), Direction::new(0.6, 0., 0.8)] {
+            // Into glass, and out of it, by the `Dielectric` convention.
+            for refraction_index in [1.5, 1. / 1.5] {
+                for (roughness, minimum) in [(0.2, 0.98), (0.6, 0.8)] {
+                    let interface = GgxInterface::new(
+                        &normal,
+                        &outgoing,
+                        Ggx::from_roughness(roughness),
+                        refraction_index,
+                    );
+                    // Every sample passes on at most the light it carries, and the densities agree with the directions chosen: those below the surface are refracted, in proportion to how much is.
+                    let (mut total, mut refracted) = (0., 0.);
+                    for _ in 0..samples {
+                        let direction = interface.generate(&mut rng);
+                        let density = interface.value(&direction, &mut rng);
+                        if density <= 0. {
+                            continue;
+                        }
+                        let weight = interface.eval(&direction) / density;
+                        assert!(weight <= 1. + 1e-6, "Gained energy: {weight}");
+                        total += weight;
+                        if direction.z < 0. {
+                            refracted += weight;
+                        }
+                    }
+                    let albedo = total / samples as f64;
+                    assert!(minimum < albedo && albedo <= 1., "{albedo}");
+                    assert!(refracted / samples as f64 > 0.5);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    f64::consts::PI,
+    ops::{Add, Div, Mul, Sub},
+    sync::Arc,
+};
+
+use rand::{Rng, rngs::SmallRng};
+
+use crate::{
+    color::Color,
+    hittables::HitRecord,
+    materials::{Material, ScatterRecord},
+    ray::Ray,
+    spectrum,
+    textures::{SolidColor, Texture},
+};
+
+// What's underneath a thin film.
+#[derive(Clone)]
+pub enum Substrate {
+    // A clear material with the given refractive index, into which the light that isn't reflected passes. 1.0 for a film with air on both sides, as in a soap bubble.
+    Dielectric(f64),
+    // A metal with complex refractive index n + ik, given for red, green and blue light as for `Conductor::from_complex_ior`. Nothing passes into it.
+    Conductor { n: Color, k: Color },
+}
+
+// A transparent film, a few hundred nanometers thick, on top of another material: a soap bubble, oil on water, or the oxide on heated metal. Light reflected off the top of the film and off the bottom interferes, more or less strongly at each wavelength depending on the film's thickness and the angle, giving colors that shift as the surface turns.
+pub struct ThinFilm {
+    thickness: Arc<dyn Texture>,
+    refraction_index: f64,
+    substrate: Substrate,
+}
+
+impl ThinFilm {
+    // A soap bubble: a film `thickness` nanometers thick, with air on both sides. Soapy water has a refractive index of about 1.33.
+    pub fn new(thickness: f64, refraction_index: f64) -> Self {
+        Self::from_texture(
+            Arc::new(SolidColor::new(Color::new(thickness, thickness, thickness))),
+            refraction_index,
+        )
+    }
+
+    // A film whose thickness varies over the surface: at each point, it's the brightness of the texture, i.e. the average of red, green and blue, in nanometers. A `NoiseTexture` between two greys, e.g. `Color::new(200., 200., 200.)` and `Color::new(800., 800., 800.)`, makes swirls like those on a real bubble.
+    pub fn from_texture(thickness: Arc<dyn Texture>, refraction_index: f64) -> Self {
+        assert!(
+            refraction_index >= 1.,
+            "Refractive index of film is too small"
+        );
+        Self {
+            thickness,
+            refraction_index,
+            substrate: Substrate::Dielectric(1.),
+        }
+    }
+
+    // Put the film on top of `substrate` rather than air.
+    pub fn with_substrate(mut self, substrate: Substrate) -> Self {
+        self.substrate = substrate;
+        self
+    }
+
+    // The fraction of each color that the film reflects, for light arriving at an angle with cosine `cosine` to the normal, from a medium with refractive index `outside` towards one whose refractive index at each wavelength is `inside`. In spectral rendering, i.e. given a hero wavelength, the fraction at each of the path's three wavelengths instead.
+    fn reflectance(
+        &self,
+        record: &HitRecord,
+        cosine: f64,
+        outside: f64,
+        inside: impl Fn(f64) -> Complex,
+        hero: Option<f64>,
+    ) -> Color {
+        let thickness = self.thickness.value(record.u, record.v, &record.point);
+        let thickness = ((thickness.r + thickness.g + thickness.b) / 3.).max(0.);
+        let at = |wavelength| {
+            airy_reflectance(
+                cosine,
+                outside,
+                self.refraction_index,
+                inside(wavelength),
+                thickness,
+                wavelength,
+            )
+        };
+        match hero {
+            Some(hero) => {
+                let [a, b, c] = spectrum::wavelengths(hero).map(at);
+                Color::new(a, b, c)
+            }
+            None => spectrum::color_of(at),
+        }
+    }
+}
+
+impl Material for ThinFilm {
+    fn scatter(
+        &self,
+        incident_ray: &Ray,
+        record: &HitRecord,
+        rng: &mut SmallRng,
+    ) -> Option<ScatterRecord> {
+        let normal = &record.normal;
+        let unit_direction = incident_ray.direction.normalize();
+        let cosine = (-unit_direction.dot(normal)).clamp(0., 1.);
+        let reflected =
+            Ray::new(record.point, unit_direction.reflect(normal)).with_time(incident_ray.time);
+
+        let substrate_index = match &self.substrate {
+            Substrate::Dielectric(refraction_index) => *refraction_index,
+            Substrate::Conductor { n, k } => {
+                let reflectance = self.reflectance(
+                    record,
+                    cosine,
+                    1.,
+                    |wavelength| {
+                        Complex::new(at_wavelength(n, wavelength), at_wavelength(k, wavelength))
+                    },
+                    incident_ray.wavelength,
+                );
+                return Some(ScatterRecord::Specular {
+                    ray: reflected,
+                    attenuation: reflectance,
+                });
+            }
+        };
+        // The film is too thin to shift the light sideways, so what passes through is bent as by a `Dielectric` with the substrate's refractive index, and goes straight on if that's 1. For the reflections to agree with that, the film is taken to lie between media whose refractive indices have the same ratio as `Dielectric` uses.
+        let refraction_index = if record.front_face {
+            substrate_index
+        } else {
+            1. / substrate_index
+        };
+        let reflectance = self.reflectance(
+            record,
+            cosine,
+            refraction_index,
+            |_| Complex::real(1.),
+            incident_ray.wavelength,
+        );
+        let sin_theta = (1. - cosine * cosine).sqrt();
+        if refraction_index * sin_theta > 1. {
+            return Some(ScatterRecord::Specular {
+                ray: reflected,
+                attenuation: Color::new(1., 1., 1.),
+            });
+        }
+
+        // Reflect or pass through at random, as often as each happens on average, weighting each color to make up for the difference.
+        let white = Color::new(1., 1., 1.);
+        let chance = ((reflectance.r + reflectance.g + reflectance.b) / 3.).clamp(1e-3, 1. - 1e-3);
+        if rng.random::<f64>() < chance {
+            Some(ScatterRecord::Specular {
+                ray: reflected,
+                attenuation: reflectance / chance,
+            })
+        } else {
+            let mut direction = unit_direction.refract(normal, refraction_index);
+            if direction.near_zero() {
+                direction = unit_direction;
+            }
+            Some(ScatterRecord::Specular {
+                ray: Ray::new(record.point, direction).with_time(incident_ray.time),
+                attenuation: (white - reflectance) / (1. - chance),
+            })
+        }
+    }
+
+    fn is_spectral(&self) -> bool {
+        true
+    }
+}
+
+// The channels of a `Color` stand for red, green and blue light, at about 650, 550 and 450 nm. In between, interpolate.
+fn at_wavelength(color: &Color, wavelength: f64) -> f64 {
+    if wavelength < 550. {
+        let t = ((wavelength - 450.) / 100.).clamp(0., 1.);
+        color.b + t * (color.g - color.b)
+    } else {
+        let t = ((wavelength - 550.) / 100.).clamp(0., 1.);
+        color.g + t * (color.r - color.g)
+    }
+}
+
+// The fraction of light of `wavelength` reflected by a film `thickness` thick (both in nanometers), with refractive index `film`, between media with refractive indices `outside`, where the light comes from, and `inside`, complex for a metal. Light is reflected back and forth between the film's two surfaces countless times, losing some each time through one surface or the other; summing the waves that leave, with their phases, gives the Airy formula. The result is averaged over the two polarizations.
+fn airy_reflectance(
+    cosine: f64,
+    outside: f64,
+    film: f64,
+    inside: Complex,
+    thickness: f64,
+    wavelength: f64,
+) -> f64 {
+    let sin_2 = 1. - cosine * cosine;
+    let n_1 = Complex::real(outside);
+    let n_2 = Complex::real(film);
+    let n_3 = inside;
+    // By Snell's law, n sin θ is the same in each layer. The cosines are complex where the light can't get through, and in metals.
+    let cos_1 = Complex::real(cosine);
+    let cos_2 = (Complex::real(1.) - Complex::real(sin_2 * (outside / film).powi(2))).sqrt();
+    let cos_3 = (Complex::real(1.) - Complex::real(sin_2 * outside * outside) / (n_3 * n_3)).sqrt();
+
+    // The amplitude reflected at each surface, for light polarized perpendicular to the plane of incidence (s) and parallel to it (p).
+    let perpendicular = |n_a: Complex, cos_a: Complex, n_b: Complex, cos_b: Complex| -> Complex {
+        (n_a * cos_a - n_b * cos_b) / (n_a * cos_a + n_b * cos_b)
+    };
+    let parallel = |n_a: Complex, cos_a: Complex, n_b: Complex, cos_b: Complex| -> Complex {
+        (n_b * cos_a - n_a * cos_b) / (n_b * cos_a + n_a * cos_b)
+    };
+
+    // The extra distance light travels, there and back, through the film, as a phase.
+    let phase = Complex::real(4. * PI * film * thickness / wavelength) * cos_2;
+    let delay = (Complex::new(0., 1.) * phase).exp();
+
+    let airy = |r_12: Complex, r_23: Complex| -> f64 {
+        let r = (r_12 + r_23 * delay) / (Complex::real(1.) + r_12 * r_23 * delay);
+        r.norm_sqr().min(1.)
+    };
+    let s = airy(
+        perpendicular(n_1, cos_1, n_2, cos_2),
+        perpendicular(n_2, cos_2, n_3, cos_3),
+    );
+    let p = airy(
+        parallel(n_1, cos_1, n_2, cos_2),
+        parallel(n_2, cos_2, n_3, cos_3),
+    );
+    0.5 * (s + p)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    fn real(re: f64) -> Self {
+        Self::new(re, 0.)
+    }
+
+    fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    // The square root with non-negative real part.
+    fn sqrt(self) -> Self {
+        let norm = self.norm_sqr().sqrt();
+        let re = (0.5 * (norm + self.re)).max(0.).sqrt();
+        let im = (0.5 * (norm - self.re)).max(0.).sqrt();
+        Self::new(re, if self.im < 0. { -im } else { im })
+    }
+
+    fn exp(self) -> Self {
+        let scale = self.re.exp();
+        Self::new(scale * self.im.cos(), scale * self.im.sin())
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let norm = other.norm_sqr();
+        Self::new(
+            (self.re * other.re + self.im * other.im) / norm,
+            (self.im * other.re - self.re * other.im) / norm,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::vec3::{Direction, Point3};
+
+    #[test]
+    fn films_interfere_as_the_textbooks_say() {
+        // With no film, the substrate's own reflectance is left: 4% for glass seen head on.
+        let glass = Complex::real(1.5);
+        let bare = airy_reflectance(1., 1., 1.5, glass, 0., 550.);
+        assert!((bare - 0.04).abs() < 1e-9);
+
+        // A film a quarter of a wavelength thick, with an index between air's and the glass's, cancels the reflection of that wavelength: how lenses are coated.
+        let coating = 1.5f64.sqrt();
+        let quarter_wave = 550. / (4. * coating);
+        let coated = airy_reflectance(1., 1., coating, glass, quarter_wave, 550.);
+        assert!(coated < 1e-9, "{coated}");
+        assert!(airy_reflectance(1., 1., coating, glass, quarter_wave, 450.) > 1e-3);
+
+        // A soap film much thinner than a wavelength reflects almost nothing: the black spots just before a bubble bursts. Thicker, it reflects some colors more than others, and which depends on the angle.
+        let air = Complex::real(1.);
+        assert!(airy_reflectance(1., 1., 1.33, air, 5., 550.) < 1e-3);
+        let head_on = [450., 550., 650.].map(|w| airy_reflectance(1., 1., 1.33, air, 300., w));
+        let slanted = [450., 550., 650.].map(|w| airy_reflectance(0.5, 1., 1.33, air, 300., w));
+        assert!(
+            head_on
+                .iter()
+                .zip(&slanted)
+                .any(|(a, b)| (a - b).abs() > 0.02)
+        );
+        for reflectance in head_on.iter().chain(&slanted) {
+            assert!((0. ..=1.).contains(reflectance));
+        }
+
+        // Past the critical angle, everything is reflected, film or not.
+        let inside_glass = airy_reflectance(0.3, 1.5, 1.33, air, 300., 550.);
+        assert!((inside_glass - 1.).abs() < 1e-9, "{inside_glass}");
+    }
+
+    #[test]
+    fn films_on_metal_reflect_no_more_than_the_light_they_receive() {
+        let gold_n = Color::new(0.143, 0.374, 1.442);
+        let gold_k = Color::new(3.983, 2.385, 1.603);
+        for thickness in [0., 100., 250., 400.] {
+            for cosine in [1., 0.7, 0.2] {
+                for wavelength in [450., 550., 650.] {
+                    let metal = Complex::new(
+                        at_wavelength(&gold_n, wavelength),
+                        at_wavelength(&gold_k, wavelength),
+                    );
+                    let reflectance =
+                        airy_reflectance(cosine, 1., 1.5, metal, thickness, wavelength);
+                    assert!(0.1 < reflectance && reflectance <= 1., "{reflectance}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn spectral_rendering_reflects_each_wavelength_exactly() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let n = Color::new(0.143, 0.374, 1.442);
+        let k = Color::new(3.983, 2.385, 1.603);
+        let film = Arc::new(
+            ThinFilm::new(300., 1.5).with_substrate(Substrate::Conductor {
+                n: n.clone(),
+                k: k.clone(),
+            }),
+        );
+        let hero = 430.;
+        let ray = Ray::new(Point3::new(-1., 1., 0.), Direction::new(1., -1., 0.))
+            .with_wavelength(Some(hero));
+        let record = HitRecord::new(
+            Point3::new(0., 0., 0.),
+            Direction::new(0., 1., 0.),
+            1.,
+            film.clone(),
+            &ray,
+        );
+        let Some(ScatterRecord::Specular { attenuation, .. }) =
+            film.scatter(&ray, &record, &mut rng)
+        else {
+            panic!("Expected a film on metal to reflect specularly");
+        };
+
+        // The film's own reflectance at the path's three wavelengths, not an RGB color to be turned into a spectrum.
+        let cosine = 0.5f64.sqrt();
+        let expected = spectrum::wavelengths(hero).map(|wavelength| {
+            let metal = Complex::new(at_wavelength(&n, wavelength), at_wavelength(&k, wavelength));
+            airy_reflectance(cosine, 1., 1.5, metal, 300., wavelength)
+        });
+        let found = [attenuation.r, attenuation.g, attenuation.b];
+        for (a, b) in found.iter().zip(expected) {
+            assert!((a - b).abs() < 1e-9, "{found:?} {expected:?}");
+        }
+        assert!(film.is_spectral());
+    }
+
+    #[test]
+    fn bubbles_pass_on_all_the_light_they_receive() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let bubble = Arc::new(ThinFilm::new(350., 1.33));
+        let ray = Ray::new(Point3::new(-1., 1., 0.), Direction::new(1., -1., 0.));
+        let record = HitRecord::new(
+            Point3::new(0., 0., 0.),
+            Direction::new(0., 1., 0.),
+            1.,
+            bubble.clone(),
+            &ray,
+        );
+
+        // Whatever isn't reflected goes straight through, so on average, all of each color carries on one way or the other.
+        let samples = 20_000;
+        let mut total = Color::new(0., 0., 0.);
+        for _ in 0..samples {
+            let Some(ScatterRecord::Specular {
+                ray: scattered,
+                attenuation,
+            }) = bubble.scatter(&ray, &record, &mut rng)
+            else {
+                panic!("Expected a bubble to scatter specularly");
+            };
+            let reflected = scattered.direction.y > 0.;
+            let straight_on = (scattered.direction - ray.direction).near_zero();
+            assert!(reflected || straight_on);
+            total = total + attenuation;
+        }
+        let mean = total / samples as f64;
+        for channel in [mean.r, mean.g, mean.b] {
+            assert!((channel - 1.).abs() < 0.02, "{mean:?}");
+        }
+    }
+}
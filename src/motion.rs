@@ -0,0 +1,91 @@
+use crate::{aabb::Aabb, vec3::Direction};
+
+// How an object moves while the camera's shutter is open: its displacement from where it was placed, as a function of time. The path is given by keyframes, each a time and a displacement, with straight-line motion between them. Before the first keyframe and after the last, the object waits where they put it. Times are on the same scale as the camera's `shutter_open` and `shutter_close`.
+#[derive(Clone, Debug, Default)]
+pub struct Motion {
+    keyframes: Vec<(f64, Direction)>, // Sorted by time. Empty for an object that doesn't move.
+}
+
+impl Motion {
+    // Not moving at all.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    // Moving steadily from where it was placed at time 0 to `displacement` away from there at time 1.
+    pub fn linear(displacement: Direction) -> Self {
+        Self::keyframes(vec![(0., Direction::new(0., 0., 0.)), (1., displacement)])
+    }
+
+    // Passing through each displacement at the time paired with it, in any order.
+    pub fn keyframes(mut keyframes: Vec<(f64, Direction)>) -> Self {
+        assert!(!keyframes.is_empty(), "Need at least one keyframe");
+        assert!(
+            keyframes.iter().all(|(time, _)| time.is_finite()),
+            "Keyframe times must be finite"
+        );
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { keyframes }
+    }
+
+    pub fn is_moving(&self) -> bool {
+        !self.keyframes.is_empty()
+    }
+
+    pub fn offset(&self, time: f64) -> Direction {
+        let Some(&(first_time, first)) = self.keyframes.first() else {
+            return Direction::new(0., 0., 0.);
+        };
+        if time <= first_time {
+            return first;
+        }
+        // The keyframes either side of `time`.
+        let next = self.keyframes.partition_point(|&(key, _)| key <= time);
+        let Some(&(end_time, end)) = self.keyframes.get(next) else {
+            return self.keyframes[next - 1].1;
+        };
+        let (start_time, start) = self.keyframes[next - 1];
+        let fraction = (time - start_time) / (end_time - start_time);
+        start + fraction * (end - start)
+    }
+
+    // The box swept out by `bbox` along the path. Motion is straight between keyframes, so the boxes at the keyframes are enough.
+    pub fn sweep(&self, bbox: &Aabb) -> Aabb {
+        if !self.is_moving() {
+            return *bbox;
+        }
+        self.keyframes
+            .iter()
+            .fold(Aabb::EMPTY, |swept, &(_, offset)| {
+                Aabb::surrounding(&swept, &bbox.translate(offset))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    #[test]
+    fn offset_between_keyframes() {
+        let motion = Motion::keyframes(vec![
+            (2., Direction::new(0., 4., 0.)),
+            (0., Direction::new(0., 0., 0.)),
+            (1., Direction::new(2., 0., 0.)),
+        ]);
+        let expect = |time: f64, expected: Direction| {
+            assert!(vec3::approx_eq(motion.offset(time), expected, 1e-12));
+        };
+        expect(-1., Direction::new(0., 0., 0.));
+        expect(0.5, Direction::new(1., 0., 0.));
+        expect(1., Direction::new(2., 0., 0.));
+        expect(1.25, Direction::new(1.5, 1., 0.));
+        expect(3., Direction::new(0., 4., 0.));
+        assert!(vec3::approx_eq(
+            Motion::none().offset(0.5),
+            Direction::new(0., 0., 0.),
+            1e-12
+        ));
+    }
+}
@@ -0,0 +1,214 @@
+use std::f64::consts::PI;
+
+use rand::{SeedableRng, rngs::SmallRng, seq::SliceRandom};
+
+use crate::vec3::{Direction, Point3};
+
+// A number that varies over space, e.g. to blend between colors or to thin out a volume.
+pub trait ScalarField: Send + Sync {
+    fn value(&self, point: &Point3) -> f64;
+}
+
+const POINT_COUNT: usize = 256;
+
+// Gradient noise: a random unit vector is fixed at each corner of a lattice of unit cubes, and the noise at a point blends the dot products of the corners' gradients with the offsets from the corners to the point. The same seed always gives the same noise.
+pub struct Perlin {
+    gradients: Vec<Direction>,
+    permutations: [Vec<usize>; 3], // One per axis, combined to hash a lattice point to a gradient.
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let gradients = (0..POINT_COUNT)
+            .map(|_| Direction::random_unit(&mut rng))
+            .collect();
+        let permutations = std::array::from_fn(|_| {
+            let mut permutation: Vec<usize> = (0..POINT_COUNT).collect();
+            permutation.shuffle(&mut rng);
+            permutation
+        });
+        Self {
+            gradients,
+            permutations,
+        }
+    }
+
+    // Smooth noise in roughly [-1, 1], which is zero at every lattice point.
+    pub fn noise(&self, point: &Point3) -> f64 {
+        let cell = [point.x.floor(), point.y.floor(), point.z.floor()];
+        let offset = Direction::new(point.x - cell[0], point.y - cell[1], point.z - cell[2]);
+        let [i, j, k] = cell.map(|coordinate| coordinate as i64);
+
+        let mut sum = 0.;
+        for corner in 0..8 {
+            let [di, dj, dk] = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+            let gradient = self.gradient(i + di, j + dj, k + dk);
+            let [dx, dy, dz] = [di, dj, dk].map(|d| d as f64);
+            let to_point = offset - Direction::new(dx, dy, dz);
+
+            // Blend with a quintic fade, so that the noise is smooth across cell boundaries.
+            let weight = |d: f64, t: f64| if d == 1. { fade(t) } else { 1. - fade(t) };
+            sum += weight(dx, offset.x)
+                * weight(dy, offset.y)
+                * weight(dz, offset.z)
+                * gradient.dot(&to_point);
+        }
+        sum
+    }
+
+    // Fractional Brownian motion: the sum of `octaves` layers of noise, each at twice the frequency and half the amplitude of the last, scaled back to roughly [-1, 1].
+    pub fn fbm(&self, point: &Point3, octaves: usize) -> f64 {
+        self.octaves(point, octaves, |noise| noise)
+    }
+
+    // Like `fbm`, but summing the absolute value of each layer, which gives creases where the noise crosses zero. In [0, 1].
+    pub fn turbulence(&self, point: &Point3, octaves: usize) -> f64 {
+        self.octaves(point, octaves, f64::abs)
+    }
+
+    fn octaves(&self, point: &Point3, octaves: usize, layer: impl Fn(f64) -> f64) -> f64 {
+        assert!(octaves > 0, "Need at least one octave");
+        let mut sum = 0.;
+        let mut total_amplitude = 0.;
+        let mut amplitude = 1.;
+        let mut frequency = 1.;
+        for _ in 0..octaves {
+            sum += amplitude * layer(self.noise(&(*point * frequency)));
+            total_amplitude += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.;
+        }
+        sum / total_amplitude
+    }
+
+    fn gradient(&self, i: i64, j: i64, k: i64) -> Direction {
+        let [x, y, z] = &self.permutations;
+        let wrap = |n: i64| (n & (POINT_COUNT as i64 - 1)) as usize;
+        self.gradients[x[wrap(i)] ^ y[wrap(j)] ^ z[wrap(k)]]
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pattern {
+    Noise,      // Plain noise: soft blobs.
+    Turbulence, // Billowy, with sharp creases.
+    Marble,     // Stripes across the x axis, distorted by turbulence into veins.
+    Wood,       // Rings around the y axis, distorted so they wobble.
+    Clouds,     // Noise whose domain is itself warped by noise, giving swirls.
+}
+
+const OCTAVES: usize = 7;
+
+// A pattern of noise, in [0, 1]. `scale` is the number of noise cells per unit of distance, so larger values give finer detail.
+pub struct NoiseField {
+    perlin: Perlin,
+    pattern: Pattern,
+    scale: f64,
+}
+
+impl NoiseField {
+    pub fn new(seed: u64, pattern: Pattern, scale: f64) -> Self {
+        assert!(1e-8 < scale, "Scale is too small");
+        Self {
+            perlin: Perlin::new(seed),
+            pattern,
+            scale,
+        }
+    }
+
+    pub fn noise(seed: u64, scale: f64) -> Self {
+        Self::new(seed, Pattern::Noise, scale)
+    }
+
+    pub fn turbulence(seed: u64, scale: f64) -> Self {
+        Self::new(seed, Pattern::Turbulence, scale)
+    }
+
+    pub fn marble(seed: u64, scale: f64) -> Self {
+        Self::new(seed, Pattern::Marble, scale)
+    }
+
+    pub fn wood(seed: u64, scale: f64) -> Self {
+        Self::new(seed, Pattern::Wood, scale)
+    }
+
+    pub fn clouds(seed: u64, scale: f64) -> Self {
+        Self::new(seed, Pattern::Clouds, scale)
+    }
+}
+
+impl ScalarField for NoiseField {
+    fn value(&self, point: &Point3) -> f64 {
+        let point = *point * self.scale;
+        let perlin = &self.perlin;
+        let value = match self.pattern {
+            Pattern::Noise => 0.5 * (1. + perlin.noise(&point)),
+            Pattern::Turbulence => perlin.turbulence(&point, OCTAVES),
+            Pattern::Marble => {
+                let phase = point.x + 3. * perlin.turbulence(&point, OCTAVES);
+                0.5 * (1. + (PI * phase).sin())
+            }
+            Pattern::Wood => {
+                let radius = point.x.hypot(point.z) + 0.4 * perlin.fbm(&point, 3);
+                radius.rem_euclid(1.)
+            }
+            Pattern::Clouds => {
+                // Offset the samples for the second and third components so that they aren't copies of the first.
+                let warp = Direction::new(
+                    perlin.fbm(&point, 4),
+                    perlin.fbm(&(point + Direction::new(5.2, 1.3, 2.8)), 4),
+                    perlin.fbm(&(point + Direction::new(1.7, 9.2, 4.1)), 4),
+                );
+                0.5 * (1. + 2. * perlin.fbm(&(point + 4. * warp), OCTAVES))
+            }
+        };
+        value.clamp(0., 1.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noise_vanishes_on_the_lattice() {
+        let perlin = Perlin::new(7);
+        for point in [
+            Point3::new(0., 0., 0.),
+            Point3::new(3., -2., 5.),
+            Point3::new(-300., 17., 256.),
+        ] {
+            assert!(perlin.noise(&point).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_noise() {
+        let point = Point3::new(0.3, 1.7, -2.2);
+        assert_eq!(Perlin::new(1).noise(&point), Perlin::new(1).noise(&point));
+        assert_ne!(Perlin::new(1).noise(&point), Perlin::new(2).noise(&point));
+    }
+
+    #[test]
+    fn patterns_stay_in_unit_interval() {
+        for pattern in [
+            Pattern::Noise,
+            Pattern::Turbulence,
+            Pattern::Marble,
+            Pattern::Wood,
+            Pattern::Clouds,
+        ] {
+            let field = NoiseField::new(3, pattern, 2.5);
+            for i in 0..200 {
+                let t = i as f64 * 0.137;
+                let value = field.value(&Point3::new(t, -0.5 * t, t.sin()));
+                assert!((0.0..=1.0).contains(&value), "{:?}: {}", pattern, value);
+            }
+        }
+    }
+}
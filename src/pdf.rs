@@ -0,0 +1,200 @@
+use std::f64::consts::PI;
+
+use rand::{Rng, rngs::SmallRng};
+
+use crate::{
+    hittables::Hittable,
+    vec3::{self, Direction, Point3},
+};
+
+// A probability density over directions, and a way to choose directions according to it. Choosing more of the directions that matter (e.g. towards lights) and dividing each one's contribution by its density gives the same average color with less noise.
+pub trait Pdf {
+    // The density, per unit solid angle, of `generate` choosing `direction`.
+    fn value(&self, direction: &Direction, rng: &mut SmallRng) -> f64;
+
+    fn generate(&self, rng: &mut SmallRng) -> Direction;
+}
+
+// Directions in the hemisphere around `normal`, more of them nearer the normal, in proportion to the cosine of the angle with it. This is how a Lambertian surface scatters light.
+pub struct CosinePdf {
+    normal: Direction,
+    tangents: [Direction; 2],
+}
+
+impl CosinePdf {
+    pub fn new(normal: &Direction) -> Self {
+        Self {
+            normal: normal.normalize(),
+            tangents: vec3::orthonormal_basis_2d(normal),
+        }
+    }
+}
+
+impl Pdf for CosinePdf {
+    fn value(&self, direction: &Direction, _rng: &mut SmallRng) -> f64 {
+        let cosine = direction.normalize().dot(&self.normal);
+        cosine.max(0.) / PI
+    }
+
+    fn generate(&self, rng: &mut SmallRng) -> Direction {
+        let (r1, r2) = (rng.random::<f64>(), rng.random::<f64>());
+        let phi = 2. * PI * r1;
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let [u, v] = self.tangents;
+        r2.sqrt() * (cos_phi * u + sin_phi * v) + (1. - r2).sqrt() * self.normal
+    }
+}
+
+// All directions equally likely.
+pub struct SpherePdf;
+
+impl Pdf for SpherePdf {
+    fn value(&self, _direction: &Direction, _rng: &mut SmallRng) -> f64 {
+        1. / (4. * PI)
+    }
+
+    fn generate(&self, rng: &mut SmallRng) -> Direction {
+        Direction::random_unit(rng)
+    }
+}
+
+// Directions from `origin` towards a shape, such as a light, as chosen by its `random_direction`.
+pub struct HittablePdf<'a> {
+    hittable: &'a dyn Hittable,
+    origin: Point3,
+    time: f64,
+}
+
+impl<'a> HittablePdf<'a> {
+    pub fn new(hittable: &'a dyn Hittable, origin: Point3, time: f64) -> Self {
+        Self {
+            hittable,
+            origin,
+            time,
+        }
+    }
+}
+
+impl Pdf for HittablePdf<'_> {
+    fn value(&self, direction: &Direction, rng: &mut SmallRng) -> f64 {
+        self.hittable
+            .pdf_value(&self.origin, direction, self.time, rng)
+    }
+
+    fn generate(&self, rng: &mut SmallRng) -> Direction {
+        self.hittable.random_direction(&self.origin, self.time, rng)
+    }
+}
+
+// Half the time one density, half the time the other. The result is never much worse than the better of the two, so it's a safe way to combine them.
+pub struct MixturePdf<'a> {
+    a: &'a dyn Pdf,
+    b: &'a dyn Pdf,
+}
+
+impl<'a> MixturePdf<'a> {
+    pub fn new(a: &'a dyn Pdf, b: &'a dyn Pdf) -> Self {
+        Self { a, b }
+    }
+}
+
+impl Pdf for MixturePdf<'_> {
+    fn value(&self, direction: &Direction, rng: &mut SmallRng) -> f64 {
+        0.5 * self.a.value(direction, rng) + 0.5 * self.b.value(direction, rng)
+    }
+
+    fn generate(&self, rng: &mut SmallRng) -> Direction {
+        if rng.random_bool(0.5) {
+            self.a.generate(rng)
+        } else {
+            self.b.generate(rng)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{
+        color::Color,
+        hittables::{disk::Disk, quad::Quad, sphere::Sphere, triangle::Triangle},
+        materials::Light,
+    };
+
+    // The average of value / density over directions chosen by the pdf estimates the integral of the value over all directions.
+    fn integrate(pdf: &dyn Pdf, mut f: impl FnMut(&Direction) -> f64, rng: &mut SmallRng) -> f64 {
+        let samples = 100_000;
+        let sum: f64 = (0..samples)
+            .map(|_| {
+                let direction = pdf.generate(rng).normalize();
+                let density = pdf.value(&direction, rng);
+                if density > 0. {
+                    f(&direction) / density
+                } else {
+                    0.
+                }
+            })
+            .sum();
+        sum / samples as f64
+    }
+
+    #[test]
+    fn pdfs_agree_on_an_integral() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let normal = Direction::new(1., 2., -0.5).normalize();
+        // The cosine-weighted hemisphere integrates to pi.
+        let f = |direction: &Direction| direction.dot(&normal).max(0.);
+
+        let cosine = CosinePdf::new(&normal);
+        assert!((integrate(&cosine, f, &mut rng) - PI).abs() < 1e-9);
+        let uniform = SpherePdf;
+        assert!((integrate(&uniform, f, &mut rng) - PI).abs() < 0.05);
+        let mixture = MixturePdf::new(&cosine, &uniform);
+        assert!((integrate(&mixture, f, &mut rng) - PI).abs() < 0.05);
+    }
+
+    #[test]
+    fn light_densities_integrate_to_one() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let light = Arc::new(Light::new(Color::new(4., 4., 4.)));
+        let shapes: [Box<dyn Hittable>; 4] = [
+            Box::new(Sphere::new(Point3::new(0., 3., 0.), 1., light.clone())),
+            Box::new(Quad::new(
+                Point3::new(-1., 2., -0.5),
+                Direction::new(2., 0., 0.),
+                Direction::new(0.5, 0., 1.),
+                light.clone(),
+            )),
+            Box::new(Disk::new(
+                Point3::new(0., 2., 0.),
+                0.8,
+                Direction::new(1., 0., 0.),
+                Direction::new(1., 0., 1.),
+                light.clone(),
+            )),
+            Box::new(Triangle::new(
+                Point3::new(-1., 2., 0.),
+                Point3::new(1., 2.5, -0.5),
+                Point3::new(0.5, 1.5, 1.),
+                light,
+            )),
+        ];
+        let origin = Point3::new(0.3, 0., 0.2);
+        for shape in &shapes {
+            let pdf = HittablePdf::new(shape.as_ref(), origin, 0.);
+            // Every direction chosen is towards the shape, and the densities over all directions add up to 1.
+            for _ in 0..100 {
+                let direction = pdf.generate(&mut rng);
+                assert!(pdf.value(&direction, &mut rng) > 0.);
+            }
+            let mut lookup_rng = SmallRng::seed_from_u64(1);
+            let density = |direction: &Direction| pdf.value(direction, &mut lookup_rng);
+            let total = integrate(&SpherePdf, density, &mut rng);
+            assert!((total - 1.).abs() < 0.05, "{total}");
+        }
+    }
+}
@@ -4,6 +4,8 @@ use crate::vec3::{Direction, Point3};
 pub struct Ray {
     pub origin: Point3,
     pub direction: Direction,
+    pub time: f64, // When the ray was sent, between the camera's shutter opening and closing.
+    pub wavelength: Option<f64>, // The hero wavelength, in nanometers, in spectral rendering; see `spectrum`.
 }
 
 impl Ray {
@@ -15,9 +17,22 @@ impl Ray {
         Ray {
             origin,
             direction: direction.normalize(),
+            time: 0.,
+            wavelength: None,
         }
     }
 
+    // Rays scattered from a surface should carry on at the same moment as the ray that hit it, so that they see moving objects where it did.
+    pub fn with_time(mut self, time: f64) -> Ray {
+        self.time = time;
+        self
+    }
+
+    pub fn with_wavelength(mut self, wavelength: Option<f64>) -> Ray {
+        self.wavelength = wavelength;
+        self
+    }
+
     pub fn at(&self, t: f64) -> Point3 {
         self.origin + self.direction * t
     }
@@ -0,0 +1,317 @@
+use std::sync::Arc;
+
+use crate::{
+    hittables::csg::Operation,
+    noise::ScalarField,
+    vec3::{Direction, Point3},
+};
+
+// A signed distance function: the distance from a point to the nearest point on a surface, negative inside it. Sphere tracing relies on there being nothing nearer than that, so it can safely step that far along a ray. Some of the combinators below only give an estimate, which may overshoot; see `SdfHittable::with_step_scale`.
+pub trait Sdf: Send + Sync {
+    fn distance(&self, point: &Point3) -> f64;
+}
+
+pub struct Sphere {
+    pub center: Point3,
+    pub radius: f64,
+}
+
+impl Sphere {
+    pub fn new(center: Point3, radius: f64) -> Self {
+        assert!(1e-8 < radius, "Radius is too small");
+        Self { center, radius }
+    }
+}
+
+impl Sdf for Sphere {
+    fn distance(&self, point: &Point3) -> f64 {
+        (*point - self.center).length() - self.radius
+    }
+}
+
+// A box, aligned with the world axes, with its edges and corners rounded off to `radius`. `half_extents` are measured to the faces, including the rounding.
+pub struct RoundedBox {
+    pub center: Point3,
+    pub half_extents: Direction,
+    pub radius: f64,
+}
+
+impl RoundedBox {
+    pub fn new(center: Point3, half_extents: Direction, radius: f64) -> Self {
+        assert!(0. <= radius, "Radius must not be negative");
+        assert!(
+            half_extents.into_iter().all(|extent| radius < extent),
+            "Half extents must be greater than the radius"
+        );
+        Self {
+            center,
+            half_extents,
+            radius,
+        }
+    }
+}
+
+impl Sdf for RoundedBox {
+    fn distance(&self, point: &Point3) -> f64 {
+        // The distance to a smaller, sharp box, less the radius. Outside, only the axes along which the point is beyond the box count; inside, the nearest face.
+        let p = *point - self.center;
+        let q = Direction::new(
+            p.x.abs() - self.half_extents.x + self.radius,
+            p.y.abs() - self.half_extents.y + self.radius,
+            p.z.abs() - self.half_extents.z + self.radius,
+        );
+        let outside = Direction::new(q.x.max(0.), q.y.max(0.), q.z.max(0.)).length();
+        let inside = q.x.max(q.y).max(q.z).min(0.);
+        outside + inside - self.radius
+    }
+}
+
+// A torus lying flat, around the vertical line through `center`.
+pub struct Torus {
+    pub center: Point3,
+    pub major_radius: f64,
+    pub minor_radius: f64,
+}
+
+impl Torus {
+    pub fn new(center: Point3, major_radius: f64, minor_radius: f64) -> Self {
+        assert!(1e-8 < minor_radius, "Minor radius is too small");
+        Self {
+            center,
+            major_radius,
+            minor_radius,
+        }
+    }
+}
+
+impl Sdf for Torus {
+    fn distance(&self, point: &Point3) -> f64 {
+        let p = *point - self.center;
+        let from_ring = p.x.hypot(p.z) - self.major_radius;
+        from_ring.hypot(p.y) - self.minor_radius
+    }
+}
+
+// The points within `radius` of the line segment from `a` to `b`.
+pub struct Capsule {
+    pub a: Point3,
+    pub b: Point3,
+    pub radius: f64,
+}
+
+impl Capsule {
+    pub fn new(a: Point3, b: Point3, radius: f64) -> Self {
+        assert!(1e-8 < radius, "Radius is too small");
+        Self { a, b, radius }
+    }
+}
+
+impl Sdf for Capsule {
+    fn distance(&self, point: &Point3) -> f64 {
+        let segment = self.b - self.a;
+        let to_point = *point - self.a;
+        let length_squared = segment.length_squared();
+        let along = if length_squared > 0. {
+            (to_point.dot(&segment) / length_squared).clamp(0., 1.)
+        } else {
+            0.
+        };
+        (to_point - along * segment).length() - self.radius
+    }
+}
+
+// Two shapes combined as in constructive solid geometry. With `smoothness` greater than 0, the seam is filleted over roughly that distance, so that, for example, two spheres melt into one another.
+pub struct Combination {
+    pub a: Arc<dyn Sdf>,
+    pub b: Arc<dyn Sdf>,
+    pub operation: Operation,
+    pub smoothness: f64,
+}
+
+impl Combination {
+    pub fn new(a: Arc<dyn Sdf>, b: Arc<dyn Sdf>, operation: Operation) -> Self {
+        Self::smooth(a, b, operation, 0.)
+    }
+
+    pub fn smooth(a: Arc<dyn Sdf>, b: Arc<dyn Sdf>, operation: Operation, smoothness: f64) -> Self {
+        assert!(0. <= smoothness, "Smoothness must not be negative");
+        Self {
+            a,
+            b,
+            operation,
+            smoothness,
+        }
+    }
+}
+
+impl Sdf for Combination {
+    fn distance(&self, point: &Point3) -> f64 {
+        let (a, b) = (self.a.distance(point), self.b.distance(point));
+        let k = self.smoothness;
+        match self.operation {
+            Operation::Union => smooth_min(a, b, k),
+            Operation::Intersection => -smooth_min(-a, -b, k),
+            Operation::Difference => -smooth_min(-a, b, k),
+        }
+    }
+}
+
+// The smaller of `a` and `b`, with the corner where they cross rounded off over a width of about `k` (a quadratic polynomial smooth minimum).
+pub fn smooth_min(a: f64, b: f64, k: f64) -> f64 {
+    if k <= 0. {
+        return a.min(b);
+    }
+    let h = (k - (a - b).abs()).max(0.) / k;
+    a.min(b) - 0.25 * h * h * k
+}
+
+// Infinitely many copies of a shape, one in each cell of a grid with spacing `period` along each axis, centered on the origin. A component of 0 means no repetition along that axis. Each copy should fit inside its own cell.
+pub struct Repeat {
+    pub inner: Arc<dyn Sdf>,
+    pub period: Direction,
+}
+
+impl Repeat {
+    pub fn new(inner: Arc<dyn Sdf>, period: Direction) -> Self {
+        assert!(
+            period.into_iter().all(|spacing| 0. <= spacing),
+            "Period must not be negative"
+        );
+        Self { inner, period }
+    }
+}
+
+impl Sdf for Repeat {
+    fn distance(&self, point: &Point3) -> f64 {
+        let wrap = |coordinate: f64, period: f64| {
+            if period > 0. {
+                coordinate - period * (coordinate / period).round()
+            } else {
+                coordinate
+            }
+        };
+        let wrapped = Point3::new(
+            wrap(point.x, self.period.x),
+            wrap(point.y, self.period.y),
+            wrap(point.z, self.period.z),
+        );
+        self.inner.distance(&wrapped)
+    }
+}
+
+// A shape twisted around the y axis, turning counterclockwise (looking down) by `degrees_per_unit` for each unit of height. Twisting stretches distances, increasingly far from the axis, so the result is only an estimate.
+pub struct Twist {
+    pub inner: Arc<dyn Sdf>,
+    radians_per_unit: f64,
+}
+
+impl Twist {
+    pub fn new(inner: Arc<dyn Sdf>, degrees_per_unit: f64) -> Self {
+        Self {
+            inner,
+            radians_per_unit: degrees_per_unit.to_radians(),
+        }
+    }
+}
+
+impl Sdf for Twist {
+    fn distance(&self, point: &Point3) -> f64 {
+        // Untwist the point, and measure the distance to the untwisted shape.
+        let (sin, cos) = (-self.radians_per_unit * point.y).sin_cos();
+        let untwisted = Point3::new(
+            cos * point.x + sin * point.z,
+            point.y,
+            -sin * point.x + cos * point.z,
+        );
+        self.inner.distance(&untwisted)
+    }
+}
+
+// A shape with its surface pushed out by `amplitude` times the value of `field`, e.g. noise to make it lumpy. The result is only an estimate, and the more the field changes over a short distance, the worse it is.
+pub struct Displace {
+    pub inner: Arc<dyn Sdf>,
+    pub field: Arc<dyn ScalarField>,
+    pub amplitude: f64,
+}
+
+impl Displace {
+    pub fn new(inner: Arc<dyn Sdf>, field: Arc<dyn ScalarField>, amplitude: f64) -> Self {
+        Self {
+            inner,
+            field,
+            amplitude,
+        }
+    }
+}
+
+impl Sdf for Displace {
+    fn distance(&self, point: &Point3) -> f64 {
+        self.inner.distance(point) - self.amplitude * self.field.value(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitives_measure_distance() {
+        let rounded = RoundedBox::new(Point3::new(0., 0., 0.), Direction::new(2., 1., 1.), 0.5);
+        assert!((rounded.distance(&Point3::new(3., 0., 0.)) - 1.).abs() < 1e-12);
+        assert!((rounded.distance(&Point3::new(0., 0., 0.)) + 1.).abs() < 1e-12);
+        // Off the corner, the distance is to the rounded corner, not to the sharp one.
+        let corner = Point3::new(2.5, 1.5, 0.);
+        let expected = 2f64.sqrt() - 0.5;
+        assert!((rounded.distance(&corner) - expected).abs() < 1e-12);
+
+        let capsule = Capsule::new(Point3::new(0., 0., 0.), Point3::new(0., 2., 0.), 0.5);
+        assert!((capsule.distance(&Point3::new(1., 1., 0.)) - 0.5).abs() < 1e-12);
+        assert!((capsule.distance(&Point3::new(0., 4., 0.)) - 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn combinations() {
+        let a: Arc<dyn Sdf> = Arc::new(Sphere::new(Point3::new(-1., 0., 0.), 1.5));
+        let b: Arc<dyn Sdf> = Arc::new(Sphere::new(Point3::new(1., 0., 0.), 1.5));
+        let origin = Point3::new(0., 0., 0.);
+        let far = Point3::new(5., 0., 0.);
+
+        let union = Combination::new(a.clone(), b.clone(), Operation::Union);
+        assert!((union.distance(&far) - 2.5).abs() < 1e-12);
+        let difference = Combination::new(a.clone(), b.clone(), Operation::Difference);
+        assert!(difference.distance(&origin) > 0.);
+
+        // Smoothing fills in the crease between them, and makes no difference far from it.
+        let smooth = Combination::smooth(a, b, Operation::Union, 0.5);
+        assert!(smooth.distance(&origin) < union.distance(&origin));
+        assert!((smooth.distance(&far) - 2.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn repeat_and_twist() {
+        let sphere: Arc<dyn Sdf> = Arc::new(Sphere::new(Point3::new(0., 0., 0.), 0.5));
+        let grid = Repeat::new(sphere, Direction::new(2., 0., 2.));
+        assert!((grid.distance(&Point3::new(10., 0., -4.)) + 0.5).abs() < 1e-12);
+        assert!((grid.distance(&Point3::new(11., 0., 0.)) - 0.5).abs() < 1e-12);
+        // Not repeated vertically.
+        assert!((grid.distance(&Point3::new(0., 2., 0.)) - 1.5).abs() < 1e-12);
+
+        // A quarter turn per unit of height carries a point on the x axis round to the z axis one unit up.
+        let bar: Arc<dyn Sdf> = Arc::new(Capsule::new(
+            Point3::new(-1., 0., 0.),
+            Point3::new(1., 0., 0.),
+            0.1,
+        ));
+        let bar = Combination::new(
+            bar,
+            Arc::new(Capsule::new(
+                Point3::new(-1., 1., 0.),
+                Point3::new(1., 1., 0.),
+                0.1,
+            )),
+            Operation::Union,
+        );
+        let twisted = Twist::new(Arc::new(bar), 90.);
+        assert!((twisted.distance(&Point3::new(0., 1., -1.)) + 0.1).abs() < 1e-12);
+    }
+}
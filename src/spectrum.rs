@@ -0,0 +1,255 @@
+use std::sync::OnceLock;
+
+use rand::{Rng, rngs::SmallRng};
+
+use crate::{color::Color, vec3::matrix::Mat3};
+
+// The range of visible wavelengths, in nanometers, that spectral rendering samples.
+pub const MIN_WAVELENGTH: f64 = 380.;
+pub const MAX_WAVELENGTH: f64 = 780.;
+const RANGE: f64 = MAX_WAVELENGTH - MIN_WAVELENGTH;
+
+// In spectral rendering, each path carries light at three wavelengths: a hero wavelength, chosen at random, and two more spaced evenly from it across the visible range, wrapping around at the ends (Wilkie et al., 2014). That way, the three channels of a `Color` can hold the amount of light at each wavelength, and almost everything else works unchanged.
+pub fn sample_hero_wavelength(rng: &mut SmallRng) -> f64 {
+    rng.random_range(MIN_WAVELENGTH..MAX_WAVELENGTH)
+}
+
+pub fn wavelengths(hero: f64) -> [f64; 3] {
+    std::array::from_fn(|i| {
+        MIN_WAVELENGTH + (hero - MIN_WAVELENGTH + i as f64 * RANGE / 3.).rem_euclid(RANGE)
+    })
+}
+
+// The reflectance, emission, etc. that `color` stands for, at the three wavelengths of `hero`, in the channels of a `Color`. Without a hero wavelength, i.e. for ordinary RGB rendering, `color` itself.
+pub fn sample(color: &Color, hero: Option<f64>) -> Color {
+    let Some(hero) = hero else {
+        return color.clone();
+    };
+    let spectrum = upsample(color);
+    let [a, b, c] = wavelengths(hero).map(|wavelength| spectrum[band(wavelength)]);
+    Color::new(a, b, c)
+}
+
+// Light at the three wavelengths of `hero`, as carried by a path, converted to the RGB color that it contributes on average.
+pub fn to_rgb(samples: &Color, hero: f64) -> Color {
+    let [a, b, c] = wavelengths(hero).map(film_response);
+    (samples.r * a + samples.g * b + samples.b * c) / 3.
+}
+
+// The color of white light once a fraction `fraction(λ)` of each wavelength λ, in nanometers, is left, as after reflecting off a material whose color comes from how it treats each wavelength, like a soap film. A constant fraction gives that shade of grey.
+pub fn color_of(fraction: impl Fn(f64) -> f64) -> Color {
+    let tables = tables();
+    let mut total = Color::new(0., 0., 0.);
+    for (i, response) in tables.color_of_responses.iter().enumerate() {
+        let wavelength = MIN_WAVELENGTH + (i as f64 + 0.5) * RANGE / COLOR_OF_STEPS as f64;
+        total = total + response.clone() * fraction(wavelength);
+    }
+    total
+}
+
+const COLOR_OF_STEPS: usize = 32;
+
+// An RGB color is turned into a spectrum that's constant across each of three bands of wavelengths: blue, green and red. The heights are chosen so that the spectrum looks like the color again when it's converted back, which is exact unless the color is too saturated for any such spectrum, in which case the negative heights needed are clamped to zero. White stays white, a flat spectrum.
+const BAND_EDGES: [f64; 2] = [490., 585.];
+
+fn band(wavelength: f64) -> usize {
+    BAND_EDGES
+        .iter()
+        .filter(|&&edge| wavelength >= edge)
+        .count()
+}
+
+fn upsample(color: &Color) -> [f64; 3] {
+    let rows = tables().rgb_to_bands.rows;
+    let rgb = [color.r, color.g, color.b];
+    // Bands run from short wavelengths to long, i.e. blue, green, red.
+    let [red, green, blue] = rows.map(|row| (0..3).map(|j| row[j] * rgb[j]).sum::<f64>().max(0.));
+    [blue, green, red]
+}
+
+// The RGB color that light of a single wavelength contributes, scaled so that a flat spectrum comes out white, i.e. averaging this over the visible range gives (1, 1, 1).
+fn film_response(wavelength: f64) -> Color {
+    let [r, g, b] = unnormalized_film_response(wavelength);
+    let [scale_r, scale_g, scale_b] = tables().film_scale;
+    Color::new(r * scale_r, g * scale_g, b * scale_b)
+}
+
+fn unnormalized_film_response(wavelength: f64) -> [f64; 3] {
+    let [x, y, z] = color_matching(wavelength);
+    XYZ_TO_LINEAR_SRGB.map(|row| row[0] * x + row[1] * y + row[2] * z)
+}
+
+const XYZ_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+    [3.2406, -1.5372, -0.4986],
+    [-0.9689, 1.8758, 0.0415],
+    [0.0557, -0.2040, 1.0570],
+];
+
+// The CIE 1931 standard observer's color matching functions: how strongly the eye's response to light of each wavelength counts towards X, Y and Z. This is the multi-lobe Gaussian fit of Wyman, Sloan and Shirley (2013).
+fn color_matching(wavelength: f64) -> [f64; 3] {
+    let lobe = |mean: f64, left: f64, right: f64| {
+        let spread = if wavelength < mean { left } else { right };
+        let t = (wavelength - mean) / spread;
+        (-0.5 * t * t).exp()
+    };
+    let x = 1.056 * lobe(599.8, 37.9, 31.0) + 0.362 * lobe(442.0, 16.0, 26.7)
+        - 0.065 * lobe(501.1, 20.4, 26.2);
+    let y = 0.821 * lobe(568.8, 46.9, 40.5) + 0.286 * lobe(530.9, 16.3, 31.1);
+    let z = 1.217 * lobe(437.0, 11.8, 36.0) + 0.681 * lobe(459.0, 26.0, 13.8);
+    [x, y, z]
+}
+
+struct Tables {
+    film_scale: [f64; 3],
+    rgb_to_bands: Mat3, // rows in the order red, green, blue
+    // The film response at each of the wavelengths `color_of` looks at, scaled so that they add up to white.
+    color_of_responses: Vec<Color>,
+}
+
+// Worked out once, by integrating over the visible range.
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let steps = 4000;
+        let step = RANGE / steps as f64;
+        let midpoints = (0..steps).map(|i| MIN_WAVELENGTH + (i as f64 + 0.5) * step);
+
+        let mut total = [0.; 3];
+        for wavelength in midpoints.clone() {
+            let response = unnormalized_film_response(wavelength);
+            (0..3).for_each(|i| total[i] += response[i] / steps as f64);
+        }
+        let film_scale = total.map(|average| 1. / average);
+
+        // The RGB color of each band at height 1, as columns in the order red, green, blue.
+        let mut bands_to_rgb = [[0.; 3]; 3];
+        for wavelength in midpoints {
+            let response = unnormalized_film_response(wavelength);
+            let column = 2 - band(wavelength);
+            (0..3).for_each(|i| {
+                bands_to_rgb[i][column] += response[i] * film_scale[i] / steps as f64;
+            });
+        }
+        let rgb_to_bands = Mat3::new(bands_to_rgb)
+            .inverse()
+            .expect("Bands should give independent colors");
+
+        let responses: Vec<[f64; 3]> = (0..COLOR_OF_STEPS)
+            .map(|i| {
+                let wavelength = MIN_WAVELENGTH + (i as f64 + 0.5) * RANGE / COLOR_OF_STEPS as f64;
+                unnormalized_film_response(wavelength)
+            })
+            .collect();
+        let white: [f64; 3] =
+            std::array::from_fn(|j| responses.iter().map(|response| response[j]).sum());
+        let color_of_responses = responses
+            .iter()
+            .map(|[r, g, b]| Color::new(r / white[0], g / white[1], b / white[2]))
+            .collect();
+
+        Tables {
+            film_scale,
+            rgb_to_bands,
+            color_of_responses,
+        }
+    })
+}
+
+// How a transparent material's refractive index varies with wavelength, which is what splits white light into colors.
+#[derive(Clone, Copy)]
+pub enum Dispersion {
+    // n = a + b / λ², with λ in micrometers: simple, and good enough for most glass in the visible range.
+    Cauchy { a: f64, b: f64 },
+    // n² = 1 + Σ bᵢ λ² / (λ² - cᵢ), with λ in micrometers, as given in glass makers' catalogs.
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    // Schott N-BK7, a common optical glass.
+    pub fn bk7() -> Self {
+        Dispersion::Sellmeier {
+            b: [1.03961212, 0.231792344, 1.01046945],
+            c: [0.00600069867, 0.0200179144, 103.560653],
+        }
+    }
+
+    // Schott N-SF11, a dense flint glass, which splits colors about two and a half times as much as BK7.
+    pub fn dense_flint() -> Self {
+        Dispersion::Sellmeier {
+            b: [1.73759695, 0.313747346, 1.89878101],
+            c: [0.013188707, 0.0623068142, 155.23629],
+        }
+    }
+
+    pub fn diamond() -> Self {
+        Dispersion::Sellmeier {
+            b: [0.3306, 4.3356, 0.],
+            c: [0.1750 * 0.1750, 0.1060 * 0.1060, 0.],
+        }
+    }
+
+    // The refractive index at `wavelength`, in nanometers.
+    pub fn refraction_index(&self, wavelength: f64) -> f64 {
+        let micrometers = wavelength / 1000.;
+        let squared = micrometers * micrometers;
+        match self {
+            Dispersion::Cauchy { a, b } => a + b / squared,
+            Dispersion::Sellmeier { b, c } => {
+                let sum: f64 = (0..3).map(|i| b[i] * squared / (squared - c[i])).sum();
+                (1. + sum).sqrt()
+            }
+        }
+    }
+}
+
+// The yellow helium line, at which refractive indices are usually quoted.
+pub const D_LINE: f64 = 587.6;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Averages over hero wavelengths spread evenly over the range, which is what random ones do in the long run.
+    fn round_trip(color: &Color) -> Color {
+        let heros = 3000;
+        let mut total = Color::new(0., 0., 0.);
+        for i in 0..heros {
+            let hero = MIN_WAVELENGTH + (i as f64 + 0.5) * RANGE / heros as f64;
+            total = total + to_rgb(&sample(color, Some(hero)), hero);
+        }
+        total / heros as f64
+    }
+
+    #[test]
+    fn colors_survive_the_round_trip_through_a_spectrum() {
+        for color in [
+            Color::new(1., 1., 1.),
+            Color::new(0.8, 0.3, 0.1),
+            Color::new(0.2, 0.5, 0.6),
+            Color::new(4., 4., 4.),
+        ] {
+            let back = round_trip(&color);
+            for (a, b) in [(back.r, color.r), (back.g, color.g), (back.b, color.b)] {
+                assert!((a - b).abs() < 1e-3 * color.r.max(1.), "{back:?} {color:?}");
+            }
+        }
+        // Reflectances stay reflectances.
+        let white = upsample(&Color::new(1., 1., 1.));
+        assert!(white.iter().all(|height| (height - 1.).abs() < 1e-9));
+        assert_eq!(
+            sample(&Color::new(0.3, 0.2, 0.1), None),
+            Color::new(0.3, 0.2, 0.1)
+        );
+    }
+
+    #[test]
+    fn glass_bends_blue_more_than_red() {
+        let bk7 = Dispersion::bk7();
+        assert!((bk7.refraction_index(D_LINE) - 1.5168).abs() < 1e-3);
+        assert!(bk7.refraction_index(450.) > bk7.refraction_index(650.));
+        let diamond = Dispersion::diamond();
+        assert!((diamond.refraction_index(589.3) - 2.417).abs() < 5e-3);
+        let cauchy = Dispersion::Cauchy { a: 1.5, b: 0.004 };
+        assert!((cauchy.refraction_index(500.) - 1.516).abs() < 1e-9);
+    }
+}
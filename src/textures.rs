@@ -0,0 +1,305 @@
+use std::{path::Path, sync::Arc};
+
+use crate::{
+    color::{self, Color},
+    interval::Interval,
+    loader::{self, LoadError},
+    noise::ScalarField,
+    vec3::{Direction, Point3},
+};
+
+// A color that varies over a surface, looked up by surface coordinates (u, v), each in the range [0, 1], or by position in space.
+pub trait Texture: Send + Sync {
+    fn value(&self, u: f64, v: f64, point: &Point3) -> Color;
+}
+
+pub struct SolidColor {
+    albedo: Color,
+}
+
+impl SolidColor {
+    pub fn new(albedo: Color) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f64, _v: f64, _point: &Point3) -> Color {
+        self.albedo.clone()
+    }
+}
+
+// A 3D checkerboard: space is divided into cubes of side `scale`, alternately filled with the two textures. Since it depends only on position, it needs no surface coordinates, and the pattern on a curved surface is where the surface cuts through the cubes.
+pub struct Checker {
+    inverse_scale: f64,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl Checker {
+    pub fn new(scale: f64, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        assert!(1e-8 < scale, "Scale is too small");
+        Self {
+            inverse_scale: 1. / scale,
+            even,
+            odd,
+        }
+    }
+
+    pub fn from_colors(scale: f64, even: Color, odd: Color) -> Self {
+        Self::new(
+            scale,
+            Arc::new(SolidColor::new(even)),
+            Arc::new(SolidColor::new(odd)),
+        )
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, u: f64, v: f64, point: &Point3) -> Color {
+        let sum: i64 = point
+            .into_iter()
+            .map(|coordinate| (self.inverse_scale * coordinate).floor() as i64)
+            .sum();
+
+        if sum.rem_euclid(2) == 0 {
+            self.even.value(u, v, point)
+        } else {
+            self.odd.value(u, v, point)
+        }
+    }
+}
+
+// A color that changes linearly from `start_color` at the plane through `start` to `end_color` at the parallel plane through `end`, staying constant beyond them.
+pub struct Gradient {
+    start: Point3,
+    direction: Direction, // From `start` to `end`, divided by its squared length.
+    start_color: Color,
+    end_color: Color,
+}
+
+impl Gradient {
+    pub fn new(start: Point3, end: Point3, start_color: Color, end_color: Color) -> Self {
+        let direction = end - start;
+        assert!(!direction.near_zero(), "Gradient start and end too close");
+        Self {
+            start,
+            direction: direction / direction.length_squared(),
+            start_color,
+            end_color,
+        }
+    }
+}
+
+impl Texture for Gradient {
+    fn value(&self, _u: f64, _v: f64, point: &Point3) -> Color {
+        let a = Interval::UNIT.clamp(self.direction.dot(&(*point - self.start)));
+        color::lerp(self.start_color.clone(), self.end_color.clone(), a)
+    }
+}
+
+// A blend of two colors, weighted at each point by a scalar field, such as one of the noise patterns of the `noise` module, clamped to [0, 1]: `low` where the field is 0, `high` where it's 1.
+pub struct NoiseTexture {
+    field: Arc<dyn ScalarField>,
+    low: Color,
+    high: Color,
+}
+
+impl NoiseTexture {
+    pub fn new(field: Arc<dyn ScalarField>, low: Color, high: Color) -> Self {
+        Self { field, low, high }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, point: &Point3) -> Color {
+        let a = Interval::UNIT.clamp(self.field.value(point));
+        color::lerp(self.low.clone(), self.high.clone(), a)
+    }
+}
+
+// A texture backed by a grid of pixels, stored row by row from the top left. The image covers the unit square of surface coordinates, with v increasing upwards. Outside the square, `wrap` decides which pixels are used.
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+    filter: Filter,
+    wrap: Wrap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    Nearest,  // The color of the pixel the point falls in: sharp, but blocky when magnified.
+    Bilinear, // A blend of the four nearest pixel centers: smooth.
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Wrap {
+    Repeat, // Tile the image.
+    Clamp,  // Extend the edge pixels.
+    Mirror, // Tile the image, flipping every other copy so the edges match up.
+}
+
+impl Wrap {
+    // The pixel index to use for `index` along a row or column of `size` pixels.
+    fn apply(self, index: i64, size: usize) -> usize {
+        let size = size as i64;
+        let wrapped = match self {
+            Wrap::Repeat => index.rem_euclid(size),
+            Wrap::Clamp => index.clamp(0, size - 1),
+            Wrap::Mirror => {
+                let index = index.rem_euclid(2 * size);
+                if index < size {
+                    index
+                } else {
+                    2 * size - 1 - index
+                }
+            }
+        };
+        wrapped as usize
+    }
+}
+
+impl ImageTexture {
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert!(width > 0 && height > 0, "Image is empty");
+        assert!(
+            pixels.len() == width * height,
+            "Number of pixels doesn't match image size"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+            filter: Filter::Nearest,
+            wrap: Wrap::Clamp,
+        }
+    }
+
+    // Load a PPM (P3 or P6) or PNG file, whose values are taken to be sRGB-encoded.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        loader::image::load(path)
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap: Wrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixel(&self, column: usize, row: usize) -> Color {
+        self.pixels[row * self.width + column].clone()
+    }
+
+    fn texel(&self, column: i64, row: i64) -> Color {
+        self.pixel(
+            self.wrap.apply(column, self.width),
+            self.wrap.apply(row, self.height),
+        )
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _point: &Point3) -> Color {
+        // Position in pixels from the top left corner, flipping v to image row order.
+        let x = u * self.width as f64;
+        let y = (1. - v) * self.height as f64;
+
+        match self.filter {
+            Filter::Nearest => self.texel(x.floor() as i64, y.floor() as i64),
+            Filter::Bilinear => {
+                // Pixel centers are at half-integer positions.
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (left, top) = (x.floor(), y.floor());
+                let (s, t) = (x - left, y - top);
+                let (left, top) = (left as i64, top as i64);
+
+                let upper = color::lerp(self.texel(left, top), self.texel(left + 1, top), s);
+                let lower =
+                    color::lerp(self.texel(left, top + 1), self.texel(left + 1, top + 1), s);
+                color::lerp(upper, lower, t)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checker_alternates_between_cells() {
+        let checker = Checker::from_colors(1., Color::new(1., 1., 1.), Color::new(0., 0., 0.));
+        let red = |x, y, z| checker.value(0., 0., &Point3::new(x, y, z)).r;
+        assert_eq!(red(0.5, 0.5, 0.5), 1.);
+        assert_eq!(red(1.5, 0.5, 0.5), 0.);
+        assert_eq!(red(-0.5, 0.5, 0.5), 0.);
+        assert_eq!(red(-0.5, -0.5, 0.5), 1.);
+    }
+
+    #[test]
+    fn gradient_is_clamped_beyond_its_ends() {
+        let gradient = Gradient::new(
+            Point3::new(0., 0., 0.),
+            Point3::new(0., 2., 0.),
+            Color::new(0., 0., 0.),
+            Color::new(1., 1., 1.),
+        );
+        let red = |x, y, z| gradient.value(0., 0., &Point3::new(x, y, z)).r;
+        assert_eq!(red(5., -1., 0.), 0.);
+        assert_eq!(red(0., 1., 3.), 0.5);
+        assert_eq!(red(0., 3., 7.), 1.);
+    }
+
+    #[test]
+    fn image_rows_run_from_the_top() {
+        let pixels = vec![Color::new(1., 1., 1.), Color::new(0., 0., 0.)];
+        let image = ImageTexture::new(1, 2, pixels);
+        let origin = Point3::new(0., 0., 0.);
+        assert_eq!(image.value(0.5, 0.9, &origin).r, 1.);
+        assert_eq!(image.value(0.5, 0.1, &origin).r, 0.);
+    }
+
+    #[test]
+    fn wrap_modes_outside_the_unit_square() {
+        let pixels = vec![
+            Color::new(0., 0., 0.),
+            Color::new(0.5, 0.5, 0.5),
+            Color::new(1., 1., 1.),
+        ];
+        let image = |wrap| ImageTexture::new(3, 1, pixels.clone()).with_wrap(wrap);
+        let origin = Point3::new(0., 0., 0.);
+        let red = |wrap, u| image(wrap).value(u, 0.5, &origin).r;
+        assert_eq!(red(Wrap::Repeat, 1.1), 0.);
+        assert_eq!(red(Wrap::Repeat, -0.1), 1.);
+        assert_eq!(red(Wrap::Clamp, 1.1), 1.);
+        assert_eq!(red(Wrap::Clamp, -5.), 0.);
+        assert_eq!(red(Wrap::Mirror, 1.1), 1.);
+        assert_eq!(red(Wrap::Mirror, -0.1), 0.);
+        assert_eq!(red(Wrap::Mirror, 1.5), 0.5);
+    }
+
+    #[test]
+    fn bilinear_blends_between_pixel_centers() {
+        let pixels = vec![Color::new(0., 0., 0.), Color::new(1., 1., 1.)];
+        let image = ImageTexture::new(2, 1, pixels).with_filter(Filter::Bilinear);
+        let origin = Point3::new(0., 0., 0.);
+        let red = |u| image.value(u, 0.5, &origin).r;
+        assert_eq!(red(0.25), 0.);
+        assert_eq!(red(0.5), 0.5);
+        assert_eq!(red(0.75), 1.);
+        assert_eq!(red(1.), 1.); // Clamped beyond the last pixel center.
+    }
+}
@@ -1,3 +1,6 @@
+pub mod matrix;
+pub mod quaternion;
+
 use std::{
     marker::PhantomData,
     ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub},
@@ -38,7 +41,7 @@ impl<T> IndexMut<usize> for Vec3<T> {
     }
 }
 
-impl<'a, T> IntoIterator for &'a Vec3<T> {
+impl<T> IntoIterator for &Vec3<T> {
     type Item = f64;
     type IntoIter = std::array::IntoIter<f64, 3>;
 
@@ -48,7 +51,7 @@ impl<'a, T> IntoIterator for &'a Vec3<T> {
 }
 
 impl<T> Vec3<T> {
-    pub fn new(x: f64, y: f64, z: f64) -> Self {
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
         Self {
             x,
             y,
@@ -98,7 +101,7 @@ impl<T> Vec3<T> {
 
 impl<T> Vec3<T> {
     pub fn zip<U>(&self, other: &Vec3<U>) -> impl Iterator<Item = (f64, f64)> {
-        std::iter::zip(self.into_iter(), other.into_iter())
+        std::iter::zip(self, other)
     }
 
     pub fn dot<U>(&self, rhs: &Vec3<U>) -> f64 {
@@ -275,7 +278,7 @@ impl IndexMut<usize> for Basis {
     }
 }
 
-impl<'a> IntoIterator for &'a Basis {
+impl IntoIterator for &Basis {
     type Item = Direction;
     type IntoIter = std::array::IntoIter<Direction, 3>;
 
@@ -284,6 +287,20 @@ impl<'a> IntoIterator for &'a Basis {
     }
 }
 
+// Two unit vectors at right angles to each other and to `axis`, such that u, v, axis form a right-handed basis.
+pub fn orthonormal_basis_2d(axis: &Direction) -> [Direction; 2] {
+    let w = axis.normalize();
+    let a = if w.x.abs() > 0.9 {
+        Direction::new(0.0, 1.0, 0.0)
+    } else {
+        Direction::new(1.0, 0.0, 0.0)
+    };
+
+    let v = w.cross(&a).normalize();
+    let u = v.cross(&w);
+    [u, v]
+}
+
 pub fn approx_eq<T>(a: Vec3<T>, b: Vec3<T>, epsilon: f64) -> bool {
     (a.x - b.x).abs() < epsilon && (a.y - b.y).abs() < epsilon && (a.z - b.z).abs() < epsilon
 }
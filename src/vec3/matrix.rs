@@ -0,0 +1,271 @@
+use std::ops::{Mul, Sub};
+
+use crate::vec3::{Basis, Direction, Point3};
+
+// A linear map of directions, as a 3x3 matrix acting on column vectors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat3 {
+    pub rows: [[f64; 3]; 3],
+}
+
+impl Mat3 {
+    pub const IDENTITY: Mat3 = Mat3 {
+        rows: [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+    };
+
+    pub fn new(rows: [[f64; 3]; 3]) -> Self {
+        Self { rows }
+    }
+
+    // The matrix that takes the x, y and z axes to `x`, `y` and `z`.
+    pub fn from_columns(x: Direction, y: Direction, z: Direction) -> Self {
+        Self::new([[x.x, y.x, z.x], [x.y, y.y, z.y], [x.z, y.z, z.z]])
+    }
+
+    // Change of basis from coordinates relative to `basis` to world coordinates.
+    pub fn from_basis(basis: &Basis) -> Self {
+        Self::from_columns(basis.x, basis.y, basis.z)
+    }
+
+    pub fn scaling(x: f64, y: f64, z: f64) -> Self {
+        Self::new([[x, 0., 0.], [0., y, 0.], [0., 0., z]])
+    }
+
+    // Rotation counterclockwise by `angle`, in radians, looking down `axis` towards the origin (Rodrigues' formula).
+    pub fn rotation(axis: Direction, angle: f64) -> Self {
+        assert!(!axis.near_zero(), "Axis vector is too small");
+        let Direction { x, y, z, .. } = axis.normalize();
+        let (sin, cos) = angle.sin_cos();
+        let c = 1. - cos;
+        Self::new([
+            [cos + x * x * c, x * y * c - z * sin, x * z * c + y * sin],
+            [y * x * c + z * sin, cos + y * y * c, y * z * c - x * sin],
+            [z * x * c - y * sin, z * y * c + x * sin, cos + z * z * c],
+        ])
+    }
+
+    // Projection onto the line through the origin along `onto`: each direction is taken to its component along `onto`.
+    pub fn projection(onto: &Direction) -> Self {
+        assert!(!onto.near_zero(), "Direction too close to zero");
+        let n = onto.normalize();
+        Self::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| n[i] * n[j])
+        }))
+    }
+
+    // Projection onto the plane through the origin perpendicular to `normal`: each direction loses its component along `normal`.
+    pub fn rejection(normal: &Direction) -> Self {
+        Self::IDENTITY - Self::projection(normal)
+    }
+
+    pub fn column(&self, j: usize) -> Direction {
+        Direction::new(self.rows[0][j], self.rows[1][j], self.rows[2][j])
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.rows[j][i])
+        }))
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.column(0).dot(&self.column(1).cross(&self.column(2)))
+    }
+
+    // None if the matrix is singular, i.e. squashes space flat.
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant == 0. || !determinant.is_finite() {
+            return None;
+        }
+
+        // The rows of the inverse are the cross products of pairs of columns, divided by the determinant.
+        let [a, b, c] = [self.column(0), self.column(1), self.column(2)];
+        let [x, y, z] = [b.cross(&c), c.cross(&a), a.cross(&b)].map(|row| row / determinant);
+        Some(Self::from_columns(x, y, z).transpose())
+    }
+}
+
+impl Sub for Mat3 {
+    type Output = Mat3;
+    fn sub(self, rhs: Mat3) -> Mat3 {
+        Mat3::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.rows[i][j] - rhs.rows[i][j])
+        }))
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Mat3;
+    fn mul(self, rhs: Mat3) -> Mat3 {
+        Mat3::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| (0..3).map(|k| self.rows[i][k] * rhs.rows[k][j]).sum())
+        }))
+    }
+}
+
+impl Mul<Direction> for Mat3 {
+    type Output = Direction;
+    fn mul(self, rhs: Direction) -> Direction {
+        let [x, y, z] = self
+            .rows
+            .map(|row| row[0] * rhs.x + row[1] * rhs.y + row[2] * rhs.z);
+        Direction::new(x, y, z)
+    }
+}
+
+// An affine map: a linear map followed by a translation. As a 4x4 matrix, it acts on column vectors (x, y, z, 1) for points and (x, y, z, 0) for directions, so points are translated and directions aren't. The bottom row is always 0, 0, 0, 1, so it isn't stored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4 {
+    pub linear: Mat3,
+    pub translation: Direction,
+}
+
+impl Mat4 {
+    pub const IDENTITY: Mat4 = Mat4 {
+        linear: Mat3::IDENTITY,
+        translation: Direction::new(0., 0., 0.),
+    };
+
+    pub fn new(linear: Mat3, translation: Direction) -> Self {
+        Self {
+            linear,
+            translation,
+        }
+    }
+
+    pub fn from_linear(linear: Mat3) -> Self {
+        Self::new(linear, Direction::new(0., 0., 0.))
+    }
+
+    pub fn translation(offset: Direction) -> Self {
+        Self::new(Mat3::IDENTITY, offset)
+    }
+
+    // The map from camera coordinates to world coordinates for a camera at `from` looking towards `at`: the camera looks down its own -z axis, with +y as close as possible to `up`.
+    pub fn look_at(from: Point3, at: Point3, up: Direction) -> Self {
+        let w = (from - at).normalize();
+        let u = up.cross(&w);
+        assert!(!u.near_zero(), "Up vector parallel to line of sight");
+        let u = u.normalize();
+        let v = w.cross(&u);
+        Self::new(Mat3::from_columns(u, v, w), from - Point3::new(0., 0., 0.))
+    }
+
+    // The full 4x4 matrix, row by row.
+    pub fn rows(&self) -> [[f64; 4]; 4] {
+        let [a, b, c] = self.linear.rows;
+        let t = self.translation;
+        [
+            [a[0], a[1], a[2], t.x],
+            [b[0], b[1], b[2], t.y],
+            [c[0], c[1], c[2], t.z],
+            [0., 0., 0., 1.],
+        ]
+    }
+
+    // None if the linear part is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let linear = self.linear.inverse()?;
+        Some(Self::new(linear, -(linear * self.translation)))
+    }
+
+    // The matrix that carries normals along with the surfaces they belong to: the inverse transpose of the linear part. Unlike other directions, normals don't simply follow the map, which would tilt them off perpendicular under non-uniform scaling. None if the linear part is singular.
+    pub fn normal_matrix(&self) -> Option<Mat3> {
+        Some(self.linear.inverse()?.transpose())
+    }
+}
+
+// `self * rhs` applies `rhs` first, then `self`.
+impl Mul for Mat4 {
+    type Output = Mat4;
+    fn mul(self, rhs: Mat4) -> Mat4 {
+        Mat4::new(
+            self.linear * rhs.linear,
+            self.linear * rhs.translation + self.translation,
+        )
+    }
+}
+
+impl Mul<Point3> for Mat4 {
+    type Output = Point3;
+    fn mul(self, rhs: Point3) -> Point3 {
+        let position = rhs - Point3::new(0., 0., 0.);
+        Point3::new(0., 0., 0.) + self.linear * position + self.translation
+    }
+}
+
+impl Mul<Direction> for Mat4 {
+    type Output = Direction;
+    fn mul(self, rhs: Direction) -> Direction {
+        self.linear * rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3;
+
+    const TOLERANCE: f64 = 1e-12;
+
+    #[test]
+    fn points_are_translated_and_directions_are_not() {
+        let map = Mat4::new(Mat3::scaling(2., 2., 2.), Direction::new(1., 0., 0.));
+        let point = map * Point3::new(1., 1., 1.);
+        let direction = map * Direction::new(1., 1., 1.);
+        assert!(vec3::approx_eq(point, Point3::new(3., 2., 2.), TOLERANCE));
+        assert!(vec3::approx_eq(
+            direction,
+            Direction::new(2., 2., 2.),
+            TOLERANCE
+        ));
+    }
+
+    #[test]
+    fn inverse_undoes_the_map() {
+        let map = Mat4::new(
+            Mat3::rotation(Direction::new(1., 2., 3.), 0.7) * Mat3::scaling(2., -0.5, 3.),
+            Direction::new(4., -1., 2.),
+        );
+        let inverse = map.inverse().unwrap();
+        let point = Point3::new(0.3, -7., 2.);
+        assert!(vec3::approx_eq(inverse * (map * point), point, TOLERANCE));
+        assert!(vec3::approx_eq((map * inverse) * point, point, TOLERANCE));
+        assert!(Mat3::scaling(1., 0., 1.).inverse().is_none());
+    }
+
+    #[test]
+    fn projection_and_rejection_split_a_direction() {
+        let axis = Direction::new(0., 3., 4.);
+        let direction = Direction::new(1., 1., 1.);
+        let along = Mat3::projection(&axis) * direction;
+        let across = Mat3::rejection(&axis) * direction;
+        assert!(vec3::approx_eq(along + across, direction, TOLERANCE));
+        assert!(along.cross(&axis).near_zero());
+        assert!(across.dot(&axis).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn normals_stay_perpendicular() {
+        let map = Mat4::from_linear(Mat3::scaling(4., 1., 1.));
+        let (tangent, normal) = (Direction::new(1., 1., 0.), Direction::new(1., -1., 0.));
+        let tangent = map * tangent;
+        let normal = map.normal_matrix().unwrap() * normal;
+        assert!(tangent.dot(&normal).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn look_at_points_minus_z_at_the_target() {
+        let from = Point3::new(1., 2., 3.);
+        let at = Point3::new(1., 2., -7.);
+        let camera = Mat4::look_at(from, at, Direction::new(0., 1., 0.));
+        let ahead = camera * Point3::new(0., 0., -10.);
+        assert!(vec3::approx_eq(ahead, at, TOLERANCE));
+        assert!(vec3::approx_eq(
+            camera * Direction::new(0., 1., 0.),
+            Direction::new(0., 1., 0.),
+            TOLERANCE
+        ));
+    }
+}
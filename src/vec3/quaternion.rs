@@ -0,0 +1,247 @@
+use std::ops::Mul;
+
+use crate::vec3::{Direction, matrix::Mat3};
+
+// A rotation, stored as a unit quaternion w + xi + yj + zk. Unlike a rotation matrix, it can be smoothly interpolated with `slerp`, e.g. to turn a camera or object over the course of an animation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Quat {
+    pub const IDENTITY: Quat = Quat {
+        w: 1.,
+        x: 0.,
+        y: 0.,
+        z: 0.,
+    };
+
+    // Rotation counterclockwise by `angle`, in radians, looking down `axis` towards the origin.
+    pub fn from_axis_angle(axis: Direction, angle: f64) -> Self {
+        assert!(!axis.near_zero(), "Axis vector is too small");
+        let axis = axis.normalize();
+        let (sin, cos) = (0.5 * angle).sin_cos();
+        Self {
+            w: cos,
+            x: sin * axis.x,
+            y: sin * axis.y,
+            z: sin * axis.z,
+        }
+    }
+
+    // The rotation described by `matrix`, which must be orthonormal with determinant 1.
+    pub fn from_mat3(matrix: &Mat3) -> Self {
+        let m = &matrix.rows;
+        let trace = m[0][0] + m[1][1] + m[2][2];
+
+        // Divide by whichever component is largest, to keep clear of dividing by zero.
+        let quat = if trace > 0. {
+            let s = 2. * (1. + trace).sqrt();
+            Self {
+                w: 0.25 * s,
+                x: (m[2][1] - m[1][2]) / s,
+                y: (m[0][2] - m[2][0]) / s,
+                z: (m[1][0] - m[0][1]) / s,
+            }
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = 2. * (1. + m[0][0] - m[1][1] - m[2][2]).sqrt();
+            Self {
+                w: (m[2][1] - m[1][2]) / s,
+                x: 0.25 * s,
+                y: (m[0][1] + m[1][0]) / s,
+                z: (m[0][2] + m[2][0]) / s,
+            }
+        } else if m[1][1] > m[2][2] {
+            let s = 2. * (1. + m[1][1] - m[0][0] - m[2][2]).sqrt();
+            Self {
+                w: (m[0][2] - m[2][0]) / s,
+                x: (m[0][1] + m[1][0]) / s,
+                y: 0.25 * s,
+                z: (m[1][2] + m[2][1]) / s,
+            }
+        } else {
+            let s = 2. * (1. + m[2][2] - m[0][0] - m[1][1]).sqrt();
+            Self {
+                w: (m[1][0] - m[0][1]) / s,
+                x: (m[0][2] + m[2][0]) / s,
+                y: (m[1][2] + m[2][1]) / s,
+                z: 0.25 * s,
+            }
+        };
+        quat.normalize()
+    }
+
+    // The rotation that turns the -z axis towards `direction`, keeping +y as close as possible to `up`, as for a camera or an object facing along `direction`.
+    pub fn look_at(direction: Direction, up: Direction) -> Self {
+        assert!(!direction.near_zero(), "Direction too close to zero");
+        let w = -direction.normalize();
+        let u = up.cross(&w);
+        assert!(!u.near_zero(), "Up vector parallel to direction");
+        let u = u.normalize();
+        let v = w.cross(&u);
+        Self::from_mat3(&Mat3::from_columns(u, v, w))
+    }
+
+    pub fn to_mat3(&self) -> Mat3 {
+        let Quat { w, x, y, z } = *self;
+        Mat3::new([
+            [
+                1. - 2. * (y * y + z * z),
+                2. * (x * y - w * z),
+                2. * (x * z + w * y),
+            ],
+            [
+                2. * (x * y + w * z),
+                1. - 2. * (x * x + z * z),
+                2. * (y * z - w * x),
+            ],
+            [
+                2. * (x * z - w * y),
+                2. * (y * z + w * x),
+                1. - 2. * (x * x + y * y),
+            ],
+        ])
+    }
+
+    // The opposite rotation.
+    pub fn conjugate(&self) -> Self {
+        Self {
+            w: self.w,
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+
+    pub fn dot(&self, other: &Quat) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn normalize(&self) -> Self {
+        let length = self.dot(self).sqrt();
+        assert!(1e-12 < length, "Quaternion too close to zero");
+        self.scale(1. / length)
+    }
+
+    // Spherical linear interpolation: turn at a steady rate from `start` (when `t` is 0) to `end` (when `t` is 1), the short way round.
+    pub fn slerp(start: &Quat, end: &Quat, t: f64) -> Self {
+        // q and -q are the same rotation; pick whichever is nearer `start`.
+        let mut cos = start.dot(end);
+        let end = if cos < 0. {
+            cos = -cos;
+            end.scale(-1.)
+        } else {
+            *end
+        };
+
+        // When the two are very close, the angle between them is too, and ordinary linear interpolation is accurate and avoids dividing by almost zero.
+        let (a, b) = if cos > 0.9995 {
+            (1. - t, t)
+        } else {
+            let angle = cos.acos();
+            let sin = angle.sin();
+            (((1. - t) * angle).sin() / sin, (t * angle).sin() / sin)
+        };
+        start.scale(a).add(&end.scale(b)).normalize()
+    }
+
+    fn scale(&self, factor: f64) -> Self {
+        Self {
+            w: self.w * factor,
+            x: self.x * factor,
+            y: self.y * factor,
+            z: self.z * factor,
+        }
+    }
+
+    fn add(&self, other: &Quat) -> Self {
+        Self {
+            w: self.w + other.w,
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+}
+
+// `self * rhs` is the rotation `rhs` followed by `self`.
+impl Mul for Quat {
+    type Output = Quat;
+    fn mul(self, rhs: Quat) -> Quat {
+        Quat {
+            w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        }
+    }
+}
+
+impl Mul<Direction> for Quat {
+    type Output = Direction;
+    fn mul(self, rhs: Direction) -> Direction {
+        // Rotate with v' = v + 2w(q × v) + 2q × (q × v), where q is the vector part, which is cheaper than q v q*.
+        let q = Direction::new(self.x, self.y, self.z);
+        let t = 2. * q.cross(&rhs);
+        rhs + self.w * t + q.cross(&t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::vec3;
+
+    const TOLERANCE: f64 = 1e-12;
+
+    #[test]
+    fn quaternion_and_matrix_rotate_alike() {
+        let axis = Direction::new(1., -2., 0.5);
+        let quat = Quat::from_axis_angle(axis, 1.1);
+        let direction = Direction::new(0.3, 0.4, -2.);
+        let by_matrix = Mat3::rotation(axis, 1.1) * direction;
+        assert!(vec3::approx_eq(quat * direction, by_matrix, TOLERANCE));
+        assert!(vec3::approx_eq(
+            quat.to_mat3() * direction,
+            by_matrix,
+            TOLERANCE
+        ));
+
+        let recovered = Quat::from_mat3(&quat.to_mat3());
+        assert!((recovered.dot(&quat).abs() - 1.).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn slerp_turns_at_a_steady_rate() {
+        let axis = Direction::new(0., 0., 1.);
+        let start = Quat::from_axis_angle(axis, 0.);
+        let end = Quat::from_axis_angle(axis, PI / 2.);
+        let x = Direction::new(1., 0., 0.);
+        let third = Quat::slerp(&start, &end, 1. / 3.) * x;
+        let expected = Direction::new((PI / 6.).cos(), (PI / 6.).sin(), 0.);
+        assert!(vec3::approx_eq(third, expected, TOLERANCE));
+
+        // The long way round from `start` to `-end` is the same rotation, so slerp takes the short way.
+        let negated = Quat {
+            w: -end.w,
+            x: -end.x,
+            y: -end.y,
+            z: -end.z,
+        };
+        let third = Quat::slerp(&start, &negated, 1. / 3.) * x;
+        assert!(vec3::approx_eq(third, expected, TOLERANCE));
+    }
+
+    #[test]
+    fn look_at_faces_the_direction() {
+        let direction = Direction::new(1., 1., 0.);
+        let quat = Quat::look_at(direction, Direction::new(0., 1., 0.));
+        let forward = quat * Direction::new(0., 0., -1.);
+        assert!(vec3::approx_eq(forward, direction.normalize(), TOLERANCE));
+    }
+}